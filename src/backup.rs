@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many old versions of a file are kept.
+const BACKUP_COUNT: usize = 3;
+
+/// Set to turn off backups.
+const DISABLE_ENV: &str = "DAGPLAN_NO_BACKUP";
+
+/// Copies `path` to `<path>.bak`, shifting older backups to `<path>.bak.1`, `<path>.bak.2` and so on.
+///
+/// Does nothing if the file doesn't exist yet or backups are turned off.
+pub fn backup(path: &Path) -> Result<(), String> {
+    if std::env::var_os(DISABLE_ENV).is_some() || !path.exists() {
+        return Ok(());
+    }
+
+    for i in (1..BACKUP_COUNT).rev() {
+        let older = backup_path(path, i - 1);
+        if older.exists() {
+            let _ = fs::rename(&older, backup_path(path, i));
+        }
+    }
+
+    fs::copy(path, backup_path(path, 0))
+        .map(|_| ())
+        .map_err(|e| format!("failed to back up {}: {e}", path.display()))
+}
+
+/// The path of the `n`th most recent backup of `path`, starting at 0.
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    if n > 0 {
+        name.push(format!(".{n}"));
+    }
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_edit_leaves_the_prior_contents_in_a_backup() {
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2025-03-28");
+        fs::write(&path, "first").unwrap();
+
+        backup(&path).unwrap();
+        fs::write(&path, "second").unwrap();
+        backup(&path).unwrap();
+        fs::write(&path, "third").unwrap();
+
+        let read = |n| fs::read_to_string(backup_path(&path, n)).unwrap();
+        let (newest, older) = (read(0), read(1));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(newest, "second");
+        assert_eq!(older, "first");
+    }
+
+    #[test]
+    fn a_failed_backup_is_returned() {
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("2025-03-28");
+        // Directories in the way of every backup, so none can be moved or written.
+        for n in 0..BACKUP_COUNT {
+            fs::create_dir_all(backup_path(&path, n).join("taken")).unwrap();
+        }
+        fs::write(&path, "first").unwrap();
+
+        let backed_up = backup(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(backed_up.unwrap_err().contains("2025-03-28"));
+    }
+}
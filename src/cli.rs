@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+
+use chrono::{Duration, NaiveDate};
+use chrono_tz::Tz;
+
+/// What the binary was asked to do.
+#[derive(Default)]
+pub enum Command {
+    /// The interactive planner.
+    #[default]
+    Tui,
+    /// Print time planned per activity between two dates, inclusive.
+    Stats { from: NaiveDate, to: NaiveDate },
+    /// Print time planned per weekday over every saved day.
+    WeekdayStats,
+    /// Print the day given by `--date`, or today, to stdout.
+    Export { format: ExportFormat },
+    /// Write every slot of every saved day as CSV, to `output` or stdout.
+    History { output: Option<PathBuf> },
+    /// Check a saved day or exported slots for problems, failing if there are any.
+    Validate { file: PathBuf },
+}
+
+/// The representations a day can be exported as.
+#[derive(Clone, Copy, Default)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Ical,
+    Csv,
+}
+
+/// Options given on the command line.
+#[derive(Default)]
+pub struct Args {
+    pub command: Command,
+    /// Send a notification this long before the active slot ends. Zero disables it.
+    pub warn_before: Duration,
+    /// Day to open instead of today.
+    pub date: Option<NaiveDate>,
+    /// Refuse all edits, for looking at days without risk of changing them.
+    pub readonly: bool,
+    /// Skip the recap notification sent when today's planning window closes.
+    pub no_summary: bool,
+    /// Send no desktop notifications at all. `.current_task` is still kept up to date.
+    pub no_notify: bool,
+    /// Keep days and activities apart from the default planner, under a directory of this name.
+    pub profile: Option<String>,
+    /// Mark the active slot with `>` instead of a clock emoji, for fonts without clock faces.
+    pub ascii: bool,
+    /// Zone to plan in instead of the system's local time.
+    pub tz: Option<Tz>,
+    /// Show start and end times rounded to this many minutes. Zero shows them as they are.
+    pub round: Duration,
+    /// Where `.current_task` and exported days go, instead of the home directory. Days are kept
+    /// here too on systems without a data directory.
+    pub home_dir: Option<PathBuf>,
+    /// Open the planner even if another instance holds the lock.
+    pub force: bool,
+    /// Leave out the requested length column when it's the same as the length, and shorten names.
+    pub compact: bool,
+    /// Show the active slot and the time left of it in the terminal title.
+    pub title: bool,
+    /// Make the name column this many characters wide, cutting longer names short.
+    pub name_width: Option<usize>,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut out = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--warn-before" => {
+                    let mins = value(&mut args, &arg)?;
+                    let mins: u32 = mins
+                        .parse()
+                        .map_err(|_| format!("invalid minutes for {arg}: {mins}"))?;
+                    out.warn_before = Duration::minutes(mins as i64);
+                }
+                "--date" => {
+                    out.date = Some(parse_date(&value(&mut args, &arg)?)?);
+                }
+                "--readonly" => out.readonly = true,
+                "--no-summary" => out.no_summary = true,
+                "--no-notify" => out.no_notify = true,
+                "--ascii" => out.ascii = true,
+                "--force" => out.force = true,
+                "--compact" => out.compact = true,
+                "--title" => out.title = true,
+                "--home-dir" => out.home_dir = Some(value(&mut args, &arg)?.into()),
+                "--round" => {
+                    let mins = value(&mut args, &arg)?;
+                    let mins: u32 = mins
+                        .parse()
+                        .map_err(|_| format!("invalid minutes for {arg}: {mins}"))?;
+                    out.round = Duration::minutes(mins as i64);
+                }
+                "--name-width" => {
+                    let width = value(&mut args, &arg)?;
+                    let width: usize = width
+                        .parse()
+                        .ok()
+                        .filter(|width| *width > 0)
+                        .ok_or_else(|| format!("invalid width for {arg}: {width}"))?;
+                    out.name_width = Some(width);
+                }
+                "--tz" => {
+                    let name = value(&mut args, &arg)?;
+                    let tz = name
+                        .parse()
+                        .map_err(|_| format!("unknown time zone: {name}"))?;
+                    out.tz = Some(tz);
+                }
+                "--profile" => {
+                    let name = value(&mut args, &arg)?;
+                    if name.is_empty() || name == ".." || name.contains(['/', '\\']) {
+                        return Err(format!("invalid profile name: {name}"));
+                    }
+                    out.profile = Some(name);
+                }
+                "stats" => {
+                    let first = value(&mut args, &arg)?;
+                    if first == "--by" {
+                        out.command = match value(&mut args, &first)?.as_str() {
+                            "weekday" => Command::WeekdayStats,
+                            other => return Err(format!("unknown stats grouping: {other}")),
+                        };
+                    } else {
+                        let from = parse_date(&first)?;
+                        let to = parse_date(&value(&mut args, &arg)?)?;
+                        out.command = Command::Stats { from, to };
+                    }
+                }
+                "export" => {
+                    out.command = Command::Export {
+                        format: ExportFormat::default(),
+                    }
+                }
+                "history" => out.command = Command::History { output: None },
+                "validate" => {
+                    out.command = Command::Validate {
+                        file: value(&mut args, &arg)?.into(),
+                    }
+                }
+                "--output" => {
+                    let Command::History { output } = &mut out.command else {
+                        return Err(format!("{arg} only applies to history"));
+                    };
+                    *output = Some(value(&mut args, &arg)?.into());
+                }
+                "--format" => {
+                    let Command::Export { format } = &mut out.command else {
+                        return Err(format!("{arg} only applies to export"));
+                    };
+                    *format = match value(&mut args, &arg)?.as_str() {
+                        "json" => ExportFormat::Json,
+                        "ical" => ExportFormat::Ical,
+                        "csv" => ExportFormat::Csv,
+                        other => return Err(format!("unknown export format: {other}")),
+                    };
+                }
+                other => return Err(format!("unknown argument: {other}")),
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {flag}"))
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date, expected YYYY-MM-DD: {s}"))
+}
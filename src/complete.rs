@@ -0,0 +1,90 @@
+/// Tab completion for a prompt. The first Tab completes the longest prefix shared by the
+/// candidates that start with the input. Further Tabs cycle through those candidates.
+#[derive(Default)]
+pub struct Completer {
+    matches: Vec<String>,
+    next: Option<usize>,
+}
+
+impl Completer {
+    /// What the input should become after a Tab, if it changes at all.
+    pub fn tab(&mut self, input: &str, candidates: &[String]) -> Option<String> {
+        if let Some(next) = self.next {
+            let completed = self.matches[next].clone();
+            self.next = Some((next + 1) % self.matches.len());
+            return (completed != input).then_some(completed);
+        }
+
+        self.matches = candidates
+            .iter()
+            .filter(|candidate| candidate.starts_with(input))
+            .cloned()
+            .collect();
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        let prefix = common_prefix(&self.matches);
+        // Cycling starts over from the first match, unless that's what the prefix already is.
+        self.next = Some(usize::from(self.matches[0] == prefix) % self.matches.len());
+        (prefix.len() > input.len()).then(|| prefix.to_string())
+    }
+
+    /// Any key other than Tab ends the cycling.
+    pub fn reset(&mut self) {
+        self.next = None;
+    }
+}
+
+fn common_prefix(words: &[String]) -> &str {
+    let first = &words[0];
+    let mut end = first.len();
+    for word in &words[1..] {
+        end = first[..end]
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(end.min(word.len()), |((i, _), _)| i);
+    }
+    &first[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn tab_completes_the_common_prefix_then_cycles() {
+        let candidates = names(&["lunch", "code review", "coding", "email"]);
+        let mut completer = Completer::default();
+
+        assert_eq!(completer.tab("c", &candidates).as_deref(), Some("cod"));
+        assert_eq!(
+            completer.tab("cod", &candidates).as_deref(),
+            Some("code review")
+        );
+        assert_eq!(
+            completer.tab("code review", &candidates).as_deref(),
+            Some("coding")
+        );
+        assert_eq!(
+            completer.tab("coding", &candidates).as_deref(),
+            Some("code review")
+        );
+
+        completer.reset();
+        assert_eq!(completer.tab("e", &candidates).as_deref(), Some("email"));
+        completer.reset();
+        assert_eq!(completer.tab("x", &candidates), None);
+    }
+
+    #[test]
+    fn common_prefix_stops_at_a_char_boundary() {
+        assert_eq!(common_prefix(&names(&["kaffe", "kafé"])), "kaf");
+        assert_eq!(common_prefix(&names(&["gym", "gym day"])), "gym");
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveTime};
+use crossterm::style::Color;
+use serde::Deserialize;
+
+use crate::slot::SlotDto;
+use crate::{naive_to_timesincemidnight, parse_duration, PlanWindow};
+
+/// `~/.config/dagplan/config.toml`, with settings like `day_start = "08:00"` and `day_length = "14h"`.
+pub fn config_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("dagplan").join("config.toml"))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    day_start: Option<String>,
+    day_length: Option<String>,
+    default_slot_name: Option<String>,
+    default_slot_length: Option<String>,
+    #[serde(default)]
+    category_colors: HashMap<String, String>,
+    time_log_dir: Option<PathBuf>,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Config {
+    pub window: PlanWindow,
+    pub slot_defaults: SlotDefaults,
+    /// Colors for slot categories, from a `[category_colors]` table like `work = "blue"`.
+    pub category_colors: HashMap<String, Color>,
+    /// Where the slots that ran are logged, one file per day.
+    pub time_log_dir: Option<PathBuf>,
+}
+
+/// What a newly inserted slot is called and how long it asks to be.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SlotDefaults {
+    pub name: String,
+    pub length: Duration,
+}
+
+impl Default for SlotDefaults {
+    fn default() -> Self {
+        let slot = SlotDto::default();
+        Self {
+            name: slot.name,
+            length: slot.config.length,
+        }
+    }
+}
+
+impl SlotDefaults {
+    pub fn slot(&self) -> SlotDto {
+        let mut slot = SlotDto {
+            name: self.name.clone(),
+            ..Default::default()
+        };
+        slot.config.length = self.length;
+        slot
+    }
+}
+
+/// Reads the config file. A missing file or key keeps the built-in default.
+pub fn load(path: &Path) -> Result<Config, String> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let s = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse(&s).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn parse(s: &str) -> Result<Config, String> {
+    let raw: RawConfig = toml::from_str(s).map_err(|e| e.message().to_string())?;
+    let mut window = PlanWindow::default();
+
+    if let Some(start) = raw.day_start {
+        let time = NaiveTime::parse_from_str(&start, "%H:%M")
+            .map_err(|_| format!("invalid day_start '{start}', expected HH:MM"))?;
+        window.start = naive_to_timesincemidnight(time);
+    }
+
+    if let Some(length) = raw.day_length {
+        window.total = parse_duration(&length)
+            .filter(|length| *length > Duration::zero())
+            .ok_or_else(|| format!("invalid day_length '{length}', expected a positive length"))?;
+    }
+
+    let mut slot_defaults = SlotDefaults::default();
+    if let Some(name) = raw.default_slot_name {
+        slot_defaults.name = name;
+    }
+
+    if let Some(length) = raw.default_slot_length {
+        slot_defaults.length = parse_duration(&length)
+            .filter(|length| *length > Duration::zero())
+            .ok_or_else(|| {
+                format!("invalid default_slot_length '{length}', expected a positive length")
+            })?;
+    }
+
+    let mut category_colors = HashMap::new();
+    for (category, color) in raw.category_colors {
+        let parsed = Color::try_from(color.as_str())
+            .map_err(|()| format!("unknown color '{color}' for category '{category}'"))?;
+        category_colors.insert(category, parsed);
+    }
+
+    Ok(Config {
+        window,
+        slot_defaults,
+        category_colors,
+        time_log_dir: raw.time_log_dir,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::{dur, t};
+
+    #[test]
+    fn config_overrides_the_default_window() {
+        let window = parse("day_start = \"08:30\"\nday_length = \"10h\"")
+            .unwrap()
+            .window;
+
+        assert_eq!(
+            window,
+            PlanWindow {
+                start: t(8, 30),
+                total: dur(10 * 60),
+            }
+        );
+        assert_eq!(parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn config_sets_new_slot_defaults() {
+        let config = parse("default_slot_name = \"focus\"\ndefault_slot_length = \"25m\"").unwrap();
+
+        assert_eq!(
+            config.slot_defaults,
+            SlotDefaults {
+                name: "focus".to_string(),
+                length: dur(25),
+            }
+        );
+        assert_eq!(config.window, PlanWindow::default());
+    }
+
+    #[test]
+    fn bad_values_are_rejected() {
+        assert!(parse("day_start = \"25:00\"").is_err());
+        assert!(parse("day_length = \"0\"").is_err());
+        assert!(parse("day_end = \"23:00\"").is_err());
+        assert!(parse("default_slot_length = \"0\"").is_err());
+        assert!(parse("[category_colors]\nwork = \"plaid\"").is_err());
+    }
+}
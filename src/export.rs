@@ -0,0 +1,177 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use vedvaring::Saved;
+
+use crate::cli::ExportFormat;
+use crate::slot::{SlotDto, SlotKind, SlotResult};
+use crate::{format_naive, stats, Day};
+
+const ICAL_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// Prints the saved plan for `date` to stdout. Fails if nothing was ever saved for that day.
+pub fn print_day(date: NaiveDate, format: ExportFormat) -> Result<(), String> {
+    let day: Saved<Day> = Saved::load(date).ok_or_else(|| format!("no plan saved for {date}"))?;
+    let day = day.read();
+
+    let out = match format {
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(&day.slots_config).map_err(|e| e.to_string())?
+        }
+        ExportFormat::Ical => to_ical(date, &day.slots()),
+        ExportFormat::Csv => to_csv(&day.slots()),
+    };
+
+    print!("{out}");
+    Ok(())
+}
+
+/// Renders the computed slots of a day as CSV, one row per slot with lengths in minutes.
+pub fn to_csv(slots: &[SlotResult]) -> String {
+    let mut out = String::from("name,start,end,minutes,break,done\n");
+
+    for slot in slots {
+        out.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            escape_csv(&slot.configured.name),
+            format_naive(slot.start),
+            format_naive(slot.start + slot.length),
+            slot.length.num_minutes(),
+            slot.configured.kind == SlotKind::Break,
+            slot.configured.done,
+        ));
+    }
+
+    out
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Writes every slot of every saved day as CSV to `output`, or stdout without one.
+pub fn write_history(output: Option<&Path>) -> Result<(), String> {
+    let days = stats::all_days();
+    let days: Vec<_> = days.iter().map(|day| day.read()).collect();
+    let csv = history_csv(days.iter().map(|day| (day.day, day.slots())));
+
+    match output {
+        Some(path) => std::fs::write(path, csv).map_err(|e| format!("{}: {e}", path.display())),
+        None => {
+            print!("{csv}");
+            Ok(())
+        }
+    }
+}
+
+/// One row per computed slot, lengths in minutes.
+pub fn history_csv(days: impl IntoIterator<Item = (NaiveDate, Arc<Vec<SlotResult>>)>) -> String {
+    let mut out = String::from("date,name,start,length,requested,fixed,warning\n");
+
+    for (date, slots) in days {
+        for slot in slots.iter() {
+            let warning = match &slot.warning {
+                Ok(()) => String::new(),
+                Err(e) => format!("{e:?}"),
+            };
+
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                date,
+                escape_csv(&slot.configured.name),
+                format_naive(slot.start),
+                slot.length.num_minutes(),
+                slot.configured.config.length.num_minutes(),
+                slot.configured.config.fixed_length,
+                warning,
+            ));
+        }
+    }
+
+    out
+}
+
+/// Renders the computed slots of a day as an iCalendar document.
+///
+/// Times are written as floating local times so calendars show them at the same wall-clock time.
+/// Slots still carrying the default name are left out.
+pub fn to_ical(day: NaiveDate, slots: &[SlotResult]) -> String {
+    let midnight = day.and_hms_opt(0, 0, 0).unwrap();
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let default_name = SlotDto::default().name;
+
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//dagplan//EN".to_string(),
+    ];
+
+    for (idx, slot) in slots.iter().enumerate() {
+        if slot.configured.name == default_name {
+            continue;
+        }
+
+        let start: NaiveDateTime = midnight + slot.start;
+        let end: NaiveDateTime = start + slot.length;
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{day}-{idx}@dagplan"));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("DTSTART:{}", start.format(ICAL_TIME_FORMAT)));
+        lines.push(format!("DTEND:{}", end.format(ICAL_TIME_FORMAT)));
+        lines.push(format!("SUMMARY:{}", escape_ical(&slot.configured.name)));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = lines.join("\r\n");
+    out.push_str("\r\n");
+    out
+}
+
+fn escape_ical(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::{calculate_slots, dur, t};
+
+    #[test]
+    fn ical_has_an_event_per_named_slot() {
+        let named = |name: &str| SlotDto {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let slots = calculate_slots(t(9, 0), dur(120), vec![named("email"), named("review")]);
+        let ical = to_ical("2025-03-28".parse().unwrap(), &slots);
+
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ical.contains("DTSTART:20250328T090000\r\nDTEND:20250328T100000\r\n"));
+        assert!(ical.contains("DTSTART:20250328T100000\r\nDTEND:20250328T110000\r\n"));
+    }
+
+    #[test]
+    fn history_has_a_header_and_a_row_per_slot() {
+        let day = |date: &str, slots: usize| {
+            let slots = calculate_slots(t(9, 0), dur(60), vec![SlotDto::default(); slots]);
+            (date.parse().unwrap(), Arc::new(slots))
+        };
+        let csv = history_csv([day("2025-03-27", 2), day("2025-03-28", 1)]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "date,name,start,length,requested,fixed,warning");
+        assert_eq!(lines[3], "2025-03-28,...,09:00,60,60,false,");
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::Action;
+
+/// `~/.config/dagplan/keys.toml`, where keys can be rebound like `Q = "quit"`.
+pub fn keys_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("dagplan").join("keys.toml"))
+}
+
+/// Reads the user's key overrides. A missing file means no overrides.
+pub fn load_overrides(path: &Path) -> Result<HashMap<char, Action>, String> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let s = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse_overrides(&s).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn parse_overrides(s: &str) -> Result<HashMap<char, Action>, String> {
+    let raw: HashMap<String, Action> = toml::from_str(s).map_err(|e| e.message().to_string())?;
+    let mut out = HashMap::new();
+
+    for (key, action) in raw {
+        let mut chars = key.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(format!("invalid key '{key}', expected a single character"));
+        };
+        out.insert(c, action);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn a_custom_mapping_moves_quit() {
+        let overrides = parse_overrides("Q = \"quit\"\nq = \"help\"").unwrap();
+        let keymap = crate::keymap_with(Some(&overrides));
+        let action = |c| {
+            keymap
+                .iter()
+                .find(|(code, mods, _)| *code == KeyCode::Char(c) && *mods == KeyModifiers::NONE)
+                .map(|(_, _, action)| *action)
+        };
+
+        assert!(action('Q') == Some(Action::Quit));
+        assert!(action('q') == Some(Action::Help));
+    }
+
+    #[test]
+    fn unknown_actions_are_rejected() {
+        assert!(parse_overrides("q = \"quite\"").is_err());
+    }
+}
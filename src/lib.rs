@@ -0,0 +1,37 @@
+//! The scheduling engine behind dagplan.
+//!
+//! Slots ask for a length, and optionally a fixed start. [`calculate_slots`] fits them into a
+//! window of the day, stretching or shrinking the elastic ones to fill the time between fixed
+//! starts.
+//!
+//! ```
+//! use dagplan::{try_calculate_slots, SlotDto, TimeSlotConfig};
+//! use dagplan::slot::{dur, t};
+//!
+//! let slot = |name: &str, mins| SlotDto {
+//!     name: name.to_string(),
+//!     config: TimeSlotConfig {
+//!         length: dur(mins),
+//!         ..Default::default()
+//!     },
+//!     ..Default::default()
+//! };
+//!
+//! // Two elastic slots asking for 1h and 3h share an 8h window in the same proportion.
+//! let slots = try_calculate_slots(t(9, 0), dur(8 * 60), vec![slot("mail", 60), slot("code", 180)])?;
+//!
+//! assert_eq!(slots[0].length, dur(2 * 60));
+//! assert_eq!(slots[1].start, t(11, 0));
+//! assert_eq!(slots[1].length, dur(6 * 60));
+//! # Ok::<(), dagplan::ScheduleError>(())
+//! ```
+
+pub mod slot;
+
+pub use slot::{
+    calculate_slots, try_calculate_slots, ScheduleError, SlotDto, SlotResult, TimeSlotConfig,
+};
+
+/// A time of day as the time since midnight. Times after midnight that still belong to the
+/// planned day go past 24 hours.
+pub type TimeSinceMidnight = chrono::Duration;
@@ -0,0 +1,86 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// A lock file holding the pid of the planner that has the days open, so a second instance
+/// doesn't overwrite its edits. Removed again when dropped.
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    /// Takes the lock at `path`. Fails if another running planner holds it, unless `force` is set.
+    /// A lock left behind by a planner that's no longer running is taken over.
+    pub fn acquire(path: &Path, force: bool) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+        }
+
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                let _ = write!(file, "{}", std::process::id());
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let holder = fs::read_to_string(path).unwrap_or_default();
+                let holder = holder.trim();
+                if !force && is_running(holder) {
+                    return Err(format!(
+                        "dagplan is already running (pid {holder}), use --force to open it anyway"
+                    ));
+                }
+                fs::write(path, std::process::id().to_string())
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+            }
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Whether the process with this pid is alive. Assumed so where that can't be checked.
+fn is_running(pid: &str) -> bool {
+    if pid.is_empty() || pid == std::process::id().to_string() {
+        return false;
+    }
+
+    !cfg!(target_os = "linux") || Path::new("/proc").join(pid).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_held_lock_is_refused_until_released() {
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("dagplan.lock");
+
+        let lock = Lock::acquire(&path, false).unwrap();
+        // Pretend another planner holds it. Pid 1 is always running.
+        fs::write(&path, "1").unwrap();
+        assert!(Lock::acquire(&path, false).is_err());
+
+        let forced = Lock::acquire(&path, true).unwrap();
+        drop(lock);
+        drop(forced);
+        assert!(!path.exists());
+
+        fs::write(&path, "").unwrap();
+        let stale = Lock::acquire(&path, false);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(stale.is_ok());
+    }
+}
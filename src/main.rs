@@ -1,69 +1,130 @@
-mod slot;
-
+mod backup;
+mod cli;
+mod complete;
+mod config;
+mod export;
+mod keys;
+mod lock;
+mod notify;
+mod recur;
+mod stats;
+mod template;
+mod timelog;
+mod validate;
+
+use cli::{Args, Command};
+use complete::Completer;
+use config::SlotDefaults;
 use crossterm::cursor::{MoveLeft, MoveTo, MoveToColumn};
-use crossterm::event::{self, read, Event, KeyCode};
+use crossterm::event::{self, read, Event, KeyCode, KeyModifiers};
 use crossterm::execute;
-use crossterm::style::{Attribute, Print, SetAttribute};
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType};
-use notify_rust::Notification;
-use slot::{calculate_slots, dur, t, SlotDto, SlotResult};
-use std::collections::HashMap;
-use std::ops::{ControlFlow, Deref};
+use crossterm::style::{Attribute, Color, Print, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType, SetTitle};
+use dagplan::slot::{
+    self, calculate_slots, dur, overcommitted_blocks, t, ScheduleError, SlotDto, SlotDtos,
+    SlotKind, SlotResult, TimeSlotConfig,
+};
+use lock::Lock;
+use notify::NotificationSink;
+use recur::{Recurrence, Recurring};
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::ops::{ControlFlow, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::sync::RwLock;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use template::Template;
 use uuid::Uuid;
 
-type TimeSinceMidnight = Duration;
+use dagplan::TimeSinceMidnight;
 
 use vedvaring::{DefaultWithId, FsTrait, Saved};
 
-use chrono::{Duration, Local, NaiveDate, NaiveTime};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
 
 use serde::{Deserialize, Serialize};
 
 type ActId = Uuid;
+#[allow(dead_code)]
 type SlotId = Uuid;
 
 use crossterm::{terminal, ExecutableCommand};
 use std::io::{self, Stdout, Write};
+use std::path::{Path, PathBuf};
 
 const DAY_OFFSET_SEC: i64 = 3 * 60 * 60;
+const DAY_SEC: i64 = 24 * 60 * 60;
+
+/// How many edits back the undo history reaches.
+const UNDO_LIMIT: usize = 100;
+
+/// The zone given with `--tz`. Local time is used without it.
+static TIME_ZONE: OnceLock<Tz> = OnceLock::new();
+
+#[cfg(test)]
+thread_local! {
+    /// What `now` returns in tests that pin the clock.
+    static PINNED_NOW: std::cell::Cell<Option<NaiveDateTime>> = const { std::cell::Cell::new(None) };
+}
+
+/// The wall-clock time right now, in the `--tz` zone if one was given. All "now" goes through here.
+fn now() -> NaiveDateTime {
+    #[cfg(test)]
+    if let Some(now) = PINNED_NOW.get() {
+        return now;
+    }
+
+    match TIME_ZONE.get() {
+        Some(tz) => Utc::now().with_timezone(tz).naive_local(),
+        None => Local::now().naive_local(),
+    }
+}
 
-fn is_past_midnight() -> bool {
-    let from_mid = Local::now()
-        .time()
-        .signed_duration_since(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+fn is_past_midnight(time: NaiveTime) -> bool {
+    let from_mid = time.signed_duration_since(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
 
     from_mid.num_seconds() < DAY_OFFSET_SEC
 }
 
+/// Times before `DAY_OFFSET_SEC` belong to the previous day, so they're counted from the previous midnight
+/// to keep a day's times increasing past midnight, e.g. 01:00 becomes 25 hours.
 fn naive_to_timesincemidnight(naive: NaiveTime) -> TimeSinceMidnight {
     let from_mid = naive.signed_duration_since(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
 
     let mut secs_since_midinght = from_mid.num_seconds();
 
     if secs_since_midinght < DAY_OFFSET_SEC {
-        secs_since_midinght += DAY_OFFSET_SEC;
+        secs_since_midinght += DAY_SEC;
     }
 
     TimeSinceMidnight::seconds(secs_since_midinght)
 }
 
 fn current_time() -> TimeSinceMidnight {
-    naive_to_timesincemidnight(Local::now().time())
+    naive_to_timesincemidnight(now().time())
 }
 
 fn current_day() -> NaiveDate {
-    let mut day = Local::now().date_naive();
+    day_of(now())
+}
+
+/// The planning day `time` belongs to, which is the previous date until `DAY_OFFSET_SEC` past midnight.
+fn day_of(time: NaiveDateTime) -> NaiveDate {
+    let mut day = time.date();
 
-    if is_past_midnight() {
+    if is_past_midnight(time.time()) {
         day = day.pred_opt().unwrap();
     }
 
     day
 }
 
+type SlotCalc<K> = Box<dyn Fn(&K) -> Vec<SlotResult>>;
+
+/// Everything the computed slots of a day depend on.
+type SlotsKey = (Vec<SlotDto>, PlanWindow);
+
 #[derive(Default)]
 pub struct SingletonCache<K: PartialEq + Clone, V>(RwLock<Option<(K, Arc<V>)>>);
 
@@ -82,23 +143,145 @@ impl<K: PartialEq + Clone, V> SingletonCache<K, V> {
 }
 
 fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(profile) = &args.profile {
+        let _ = PROFILE.set(profile.clone());
+    }
+
+    let fallback = args
+        .home_dir
+        .clone()
+        .or_else(|| std::env::var_os("XDG_STATE_HOME").map(PathBuf::from));
+    if let Some(dir) = fallback {
+        let _ = DATA_FALLBACK.set(dir);
+    }
+
+    if let Some(tz) = args.tz {
+        let _ = TIME_ZONE.set(tz);
+    }
+
+    if let Some(path) = config::config_path() {
+        match config::load(&path) {
+            Ok(config) => {
+                let _ = DEFAULT_WINDOW.set(config.window);
+                let _ = SLOT_DEFAULTS.set(config.slot_defaults);
+                let _ = CATEGORY_COLORS.set(config.category_colors);
+                if let Some(dir) = config.time_log_dir {
+                    let _ = TIME_LOG_DIR.set(dir);
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    match args.command {
+        Command::Stats { from, to } => {
+            stats::print_activity_stats(from, to);
+            return;
+        }
+        Command::WeekdayStats => {
+            stats::print_weekday_stats();
+            return;
+        }
+        Command::Export { format } => {
+            let date = args.date.unwrap_or_else(current_day);
+            if let Err(e) = export::print_day(date, format) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::History { output } => {
+            if let Err(e) = export::write_history(output.as_deref()) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::Validate { file } => {
+            match validate::check_file(&file, default_window()) {
+                Ok(problems) if problems.is_empty() => println!("{}: ok", file.display()),
+                Ok(problems) => {
+                    for problem in problems {
+                        println!("{}: {problem}", file.display());
+                    }
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Command::Tui => {}
+    }
+
+    if let Some(path) = keys::keys_path() {
+        match keys::load_overrides(&path) {
+            Ok(overrides) => {
+                let _ = KEY_OVERRIDES.set(overrides);
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     let date = NaiveDate::from_ymd_opt(2025, 3, 28).unwrap();
     println!("Date: {}", date);
 
-    let mut app = App::start();
+    // Readonly instances don't write, so they can run next to the one holding the lock.
+    let lock = if args.readonly {
+        None
+    } else {
+        let path = profile_root(Day::crate_name()).join("dagplan.lock");
+        match Lock::acquire(&path, args.force) {
+            Ok(lock) => Some(lock),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    };
+    let lock_path = lock.as_ref().map(|lock| lock.path().to_path_buf());
+    let title = args.title;
+
+    let mut app = App::start(args);
 
     enable_raw_mode().unwrap();
 
-    std::panic::set_hook(Box::new(|info| {
+    std::panic::set_hook(Box::new(move |info| {
         let _ = terminal::disable_raw_mode();
+        if title {
+            let _ = execute!(io::stdout(), SetTitle(""));
+        }
+        if let Some(path) = &lock_path {
+            let _ = std::fs::remove_file(path);
+        }
         eprintln!("Panic: {info}");
     }));
 
     app.run();
+    app.flush_all();
+    if title {
+        let _ = execute!(io::stdout(), SetTitle(""));
+    }
     disable_raw_mode().unwrap();
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
 struct Cursor {
     index: usize,
     field: Field,
@@ -116,30 +299,44 @@ impl Cursor {
         }
     }
 
-    fn left(&mut self) {
-        self.field = match self.field {
-            Field::Name => Field::Name,
-            Field::Start => Field::Name,
-            Field::Requested => Field::Start,
-            Field::Length => Field::Requested,
-        };
+    /// Moves to the nearest column on the left that isn't hidden, if there is one.
+    fn left(&mut self, hidden: &[Field]) {
+        let pos = self.field.position();
+        if let Some(field) = Field::ALL[..pos]
+            .iter()
+            .rev()
+            .find(|field| !hidden.contains(field))
+        {
+            self.field = *field;
+        }
     }
-    fn right(&mut self) {
-        self.field = match self.field {
-            Field::Name => Field::Start,
-            Field::Start => Field::Length,
-            Field::Requested => Field::Length,
-            Field::Length => Field::Length,
-        };
+
+    /// Moves to the nearest column on the right that isn't hidden, if there is one.
+    fn right(&mut self, hidden: &[Field]) {
+        let pos = self.field.position();
+        if let Some(field) = Field::ALL[pos + 1..]
+            .iter()
+            .find(|field| !hidden.contains(field))
+        {
+            self.field = *field;
+        }
     }
 }
 
-fn print_styled(stdout: &mut Stdout, text: &str, attrs: Vec<Attribute>) -> io::Result<()> {
+fn print_styled(
+    stdout: &mut Stdout,
+    text: &str,
+    attrs: Vec<Attribute>,
+    color: Option<Color>,
+) -> io::Result<()> {
     stdout.execute(SetAttribute(Attribute::Reset))?;
 
     for attr in attrs {
         stdout.execute(SetAttribute(attr))?;
     }
+    if let Some(color) = color {
+        stdout.execute(SetForegroundColor(color))?;
+    }
     stdout.execute(Print(text))?;
 
     // Reset style after printing
@@ -147,29 +344,95 @@ fn print_styled(stdout: &mut Stdout, text: &str, attrs: Vec<Attribute>) -> io::R
     Ok(())
 }
 
-#[derive(Copy, Clone, Default, Eq, PartialEq)]
+#[derive(Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 enum Field {
     #[default]
     Name,
     Length,
     Start,
     Requested,
+    Weight,
+    End,
+}
+
+impl Field {
+    /// The columns in the order they're drawn.
+    const ALL: [Field; 6] = [
+        Field::Name,
+        Field::Start,
+        Field::End,
+        Field::Requested,
+        Field::Length,
+        Field::Weight,
+    ];
+
+    fn position(self) -> usize {
+        Field::ALL.iter().position(|field| *field == self).unwrap()
+    }
 }
 
 struct App {
     stdout: Stdout,
     cursor: Cursor,
-    selected_day: Saved<Day>,
-    days: HashMap<NaiveDate, Saved<Day>>,
+    selected_day: LoadedDay,
+    days: HashMap<NaiveDate, LoadedDay>,
+    /// Previous states of the selected day's slots, most recent last.
+    undo_stack: Vec<SlotDtos>,
+    redo_stack: Vec<SlotDtos>,
+    /// How long before the end of a slot to warn that it's ending. Zero means never.
+    warn_before: Duration,
+    /// Slots of the current day that have already been warned about, by their start time.
+    warned_slots: HashSet<(NaiveDate, TimeSinceMidnight)>,
+    /// Ignore every action that would change a day.
+    readonly: bool,
+    /// Lowercased query of the last search, for jumping to the next match.
+    last_search: Option<String>,
+    /// Send desktop notifications. Without it only `.current_task` tells of a new slot.
+    notify: bool,
+    /// Notify with a recap when today's planning window ends.
+    summary: bool,
+    /// The last day a recap was sent for.
+    summarized: Option<NaiveDate>,
+    /// Show the diagnostics pane below the slots.
+    show_diagnostics: bool,
+    /// Show how the time of the cursor's block is shared out.
+    show_allocation: bool,
+    /// Stick to ASCII for the active slot marker.
+    ascii: bool,
+    /// Shown under the date until the next action.
+    notice: Option<String>,
+    /// What displayed start and end times are rounded to.
+    round: Duration,
+    /// Where `.current_task` and exported days go. Nothing is written there if it's unknown.
+    home_dir: Option<PathBuf>,
+    /// Hide columns that add nothing and keep names short, for narrow terminals.
+    compact: bool,
+    /// Keep the terminal title on the active slot.
+    title: bool,
+    /// The width of the name column, if set on the command line.
+    name_width: Option<usize>,
+    /// The first slot shown, when the day doesn't fit on screen.
+    scroll_offset: usize,
+    /// How many slot rows fit on screen, as of the last draw.
+    visible_rows: usize,
+    /// Where visual mode was started. The slots from here to the cursor are selected.
+    visual_anchor: Option<usize>,
+    /// When the active slot became active, for the time log.
+    slot_since: NaiveDateTime,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum Action {
     Down,
     Up,
+    PageDown,
+    PageUp,
     Left,
     Right,
     Tomorrow,
     Yesterday,
+    Today,
     Insert,
     Delete,
     Quit,
@@ -177,59 +440,325 @@ enum Action {
     Upswap,
     Downswap,
     Begin,
+    Undo,
+    Redo,
+    Help,
+    ExportIcal,
+    ExportJson,
+    ImportJson,
+    ToggleBreak,
+    JumpToNow,
+    GotoDate,
+    ToggleDone,
+    WeekView,
+    Duplicate,
+    PickAct,
+    Search,
+    SearchNext,
+    Split,
+    Merge,
+    ToggleDiagnostics,
+    ToggleAllocation,
+    ClearDay,
+    ToggleFiller,
+    JumpDays,
+    InsertBelow,
+    InsertTemplate,
+    BeginCascade,
+    CopyPrevious,
+    Visual,
+    EditNote,
+    SetCategory,
+    SetLabel,
+    ShiftStarts,
+    CycleRecurrence,
+    ToggleFrozen,
+    ToggleFixedAsScheduled,
+    AdoptLengths,
+    EvenOut,
+    BorrowFromNext,
+    AddBanner,
+    RemoveBanner,
+    FillToNext,
 }
 
+/// The built-in key bindings. Both `Action::from_event` and the help overlay read them through `keymap`.
+const KEYMAP: &[(KeyCode, KeyModifiers, Action)] = {
+    use KeyCode as KC;
+    const NONE: KeyModifiers = KeyModifiers::NONE;
+
+    &[
+        (KC::Down, NONE, Action::Down),
+        (KC::Char('j'), NONE, Action::Down),
+        (KC::Up, NONE, Action::Up),
+        (KC::Char('k'), NONE, Action::Up),
+        (KC::PageDown, NONE, Action::PageDown),
+        (KC::PageUp, NONE, Action::PageUp),
+        (KC::Left, NONE, Action::Left),
+        (KC::Char('h'), NONE, Action::Left),
+        (KC::Right, NONE, Action::Right),
+        (KC::Char('l'), NONE, Action::Right),
+        (KC::Enter, NONE, Action::Edit),
+        (KC::Insert, NONE, Action::Insert),
+        (KC::Char('i'), NONE, Action::Insert),
+        (KC::Char('O'), NONE, Action::Insert),
+        (KC::Char('o'), NONE, Action::InsertBelow),
+        (KC::Char('T'), NONE, Action::InsertTemplate),
+        (KC::Delete, NONE, Action::Delete),
+        (KC::Char('C'), NONE, Action::ClearDay),
+        (KC::Char('y'), NONE, Action::Duplicate),
+        (KC::Char('V'), NONE, Action::Visual),
+        (KC::Char('P'), NONE, Action::CopyPrevious),
+        (KC::Char('s'), NONE, Action::Split),
+        (KC::Char('M'), NONE, Action::Merge),
+        (KC::Char('a'), NONE, Action::PickAct),
+        (KC::Char('c'), NONE, Action::EditNote),
+        (KC::Char('K'), NONE, Action::SetCategory),
+        (KC::Char('r'), NONE, Action::Upswap),
+        (KC::Char('f'), NONE, Action::Downswap),
+        (KC::Char('b'), NONE, Action::Begin),
+        (KC::Char('B'), NONE, Action::BeginCascade),
+        (KC::Char('S'), NONE, Action::ShiftStarts),
+        (KC::Char('R'), NONE, Action::CycleRecurrence),
+        (KC::Char('p'), NONE, Action::ToggleBreak),
+        (KC::Char('z'), NONE, Action::ToggleFiller),
+        (KC::Char('F'), NONE, Action::ToggleFrozen),
+        (KC::Char('Y'), NONE, Action::ToggleFixedAsScheduled),
+        (KC::Char('='), NONE, Action::AdoptLengths),
+        (KC::Char('W'), NONE, Action::EvenOut),
+        (KC::Char('+'), NONE, Action::BorrowFromNext),
+        (KC::Char('>'), NONE, Action::FillToNext),
+        (KC::Char('x'), NONE, Action::ToggleDone),
+        (KC::Char('.'), NONE, Action::JumpToNow),
+        (KC::Char('/'), NONE, Action::Search),
+        (KC::Char('N'), NONE, Action::SearchNext),
+        (KC::Char('m'), NONE, Action::Tomorrow),
+        (KC::Char('n'), NONE, Action::Yesterday),
+        (KC::Char('t'), NONE, Action::Today),
+        (KC::Char('g'), NONE, Action::GotoDate),
+        (KC::Char(':'), NONE, Action::JumpDays),
+        (KC::Char('w'), NONE, Action::WeekView),
+        (KC::Char('L'), NONE, Action::SetLabel),
+        (KC::Char('A'), NONE, Action::AddBanner),
+        (KC::Char('X'), NONE, Action::RemoveBanner),
+        (KC::Char('D'), NONE, Action::ToggleDiagnostics),
+        (KC::Char('Z'), NONE, Action::ToggleAllocation),
+        (KC::Char('u'), NONE, Action::Undo),
+        (KC::Char('r'), KeyModifiers::CONTROL, Action::Redo),
+        (KC::Char('e'), NONE, Action::ExportIcal),
+        (KC::Char('E'), NONE, Action::ExportJson),
+        (KC::Char('I'), NONE, Action::ImportJson),
+        (KC::Char('?'), NONE, Action::Help),
+        (KC::Char('q'), NONE, Action::Quit),
+        (KC::Esc, NONE, Action::Quit),
+    ]
+};
+
 impl Action {
     fn from_event(event: Event) -> Option<Self> {
         let Event::Key(key) = event else {
             return None;
         };
 
-        use crossterm::event::KeyCode as KC;
-
-        match key.code {
-            KC::Backspace => None,
-            KC::Enter => Action::Edit.into(),
-            KC::Left => Some(Action::Left),
-            KC::Right => Some(Action::Right),
-            KC::Up => Some(Action::Up),
-            KC::Down => Some(Action::Down),
-            KC::Home => None,
-            KC::End => None,
-            KC::PageUp => None,
-            KC::PageDown => None,
-            KC::Tab => None,
-            KC::BackTab => None,
-            KC::Delete => Some(Action::Delete),
-            KC::Insert => Some(Action::Insert),
-            KC::F(_) => None,
-            KC::Char('j') => Some(Action::Down),
-            KC::Char('k') => Some(Action::Up),
-            KC::Char('h') => Some(Action::Left),
-            KC::Char('l') => Some(Action::Right),
-            KC::Char('i') => Some(Action::Insert),
-            KC::Char('q') => Some(Action::Quit),
-            KC::Char('r') => Some(Action::Upswap),
-            KC::Char('f') => Some(Action::Downswap),
-            KC::Char('b') => Some(Action::Begin),
-            KC::Char('m') => Some(Action::Tomorrow),
-            KC::Char('n') => Some(Action::Yesterday),
-            KC::Char(_) => None,
-            KC::Null => None,
-            KC::Esc => Some(Action::Quit),
-            KC::CapsLock => None,
-            KC::ScrollLock => None,
-            KC::NumLock => None,
-            KC::PrintScreen => None,
-            KC::Pause => None,
-            KC::Menu => None,
-            KC::KeypadBegin => None,
-            KC::Media(_) => None,
-            KC::Modifier(_) => None,
+        // Shift is already reflected in the character itself, e.g. '?' or 'B'.
+        let modifiers = key.modifiers - KeyModifiers::SHIFT;
+
+        keymap()
+            .into_iter()
+            .find(|(code, mods, _)| *code == key.code && *mods == modifiers)
+            .map(|(_, _, action)| action)
+    }
+
+    /// Whether the action changes the slots of a day.
+    fn is_mutating(&self) -> bool {
+        match self {
+            Action::Insert
+            | Action::InsertBelow
+            | Action::InsertTemplate
+            | Action::Delete
+            | Action::Edit
+            | Action::Upswap
+            | Action::Downswap
+            | Action::Begin
+            | Action::BeginCascade
+            | Action::Undo
+            | Action::Redo
+            | Action::ImportJson
+            | Action::ToggleBreak
+            | Action::ToggleFiller
+            | Action::ToggleDone
+            | Action::Duplicate
+            | Action::Split
+            | Action::ClearDay
+            | Action::CopyPrevious
+            | Action::Merge
+            | Action::PickAct
+            | Action::EditNote
+            | Action::SetCategory
+            | Action::SetLabel
+            | Action::ShiftStarts
+            | Action::CycleRecurrence
+            | Action::ToggleFrozen
+            | Action::ToggleFixedAsScheduled
+            | Action::AdoptLengths
+            | Action::EvenOut
+            | Action::BorrowFromNext
+            | Action::AddBanner
+            | Action::RemoveBanner
+            | Action::FillToNext => true,
+            Action::Down
+            | Action::Up
+            | Action::PageDown
+            | Action::PageUp
+            | Action::Left
+            | Action::Right
+            | Action::Tomorrow
+            | Action::Yesterday
+            | Action::Today
+            | Action::Quit
+            | Action::Help
+            | Action::ExportIcal
+            | Action::ExportJson
+            | Action::JumpToNow
+            | Action::GotoDate
+            | Action::JumpDays
+            | Action::WeekView
+            | Action::Visual
+            | Action::ToggleDiagnostics
+            | Action::ToggleAllocation
+            | Action::Search
+            | Action::SearchNext => false,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Action::Down => "move cursor down",
+            Action::Up => "move cursor up",
+            Action::PageDown => "move cursor a screen down",
+            Action::PageUp => "move cursor a screen up",
+            Action::Left => "move cursor to previous column",
+            Action::Right => "move cursor to next column",
+            Action::Tomorrow => "go to next day",
+            Action::Yesterday => "go to previous day",
+            Action::Today => "go to today",
+            Action::Insert => "insert a new slot at the cursor",
+            Action::InsertBelow => "insert a new slot below the cursor",
+            Action::InsertTemplate => "insert the slots of a template from templates.json at the cursor",
+            Action::Delete => "delete slot",
+            Action::ClearDay => "delete every slot of the day",
+            Action::CopyPrevious => "replace the day with the previous day's slots",
+            Action::Quit => "quit",
+            Action::Edit => {
+                "edit name / toggle start or soft start / set end / set length / toggle fixed length / set weight"
+            }
+            Action::Upswap => "swap slot with the one above",
+            Action::Downswap => "swap slot with the one below",
+            Action::Begin => "set slot start to the current time",
+            Action::BeginCascade => "start slot now, dropping later starts that come before it",
+            Action::Undo => "undo last edit",
+            Action::Redo => "redo last undone edit",
+            Action::Help => "show this help",
+            Action::ExportIcal => "export day to ~/dagplan-<date>.ics",
+            Action::ExportJson => "export day to ~/dagplan-<date>.json",
+            Action::ImportJson => "replace day with ~/dagplan-<date>.json",
+            Action::ToggleBreak => "toggle slot between activity and break",
+            Action::ToggleFiller => "toggle slot between activity and filler for leftover time",
+            Action::JumpToNow => "move cursor to the slot happening now",
+            Action::GotoDate => "go to a date (YYYY-MM-DD)",
+            Action::JumpDays => "go some days forward or back (+7, -3) or to today",
+            Action::ToggleDone => "mark slot as done / not done",
+            Action::WeekView => "show overview of the week",
+            Action::ToggleDiagnostics => "show / hide scheduling problems",
+            Action::ToggleAllocation => "show / hide how the cursor's block shares out its time",
+            Action::Duplicate => "duplicate slot below itself",
+            Action::Visual => "select slots with j / k, then set all their lengths with enter",
+            Action::Split => "split slot into two halves",
+            Action::Merge => "merge slot with the one below",
+            Action::PickAct => "assign a saved activity to the slot",
+            Action::EditNote => "write a note under the slot, empty to remove it",
+            Action::SetCategory => "put slot in a category, like \"work\", empty to remove it",
+            Action::FillToNext => "fix slot length to last until the next fixed start",
+            Action::ToggleFrozen => "fix every length in the block as scheduled now / unfix them",
+            Action::ToggleFixedAsScheduled => {
+                "toggle fixed length, keeping the length it's scheduled as when unfixing"
+            }
+            Action::AdoptLengths => "set every requested length to the length it's scheduled as",
+            Action::EvenOut => "share each block's free time equally among its elastic slots",
+            Action::BorrowFromNext => "lengthen slot by time taken from the one below",
+            Action::CycleRecurrence => "put slot in new days: daily / on weekdays / not anymore",
+            Action::ShiftStarts => "move every fixed start later or earlier, like +30 or -1h",
+            Action::AddBanner => "add an all-day note to the day, like \"on-call\"",
+            Action::RemoveBanner => "remove an all-day note",
+            Action::SetLabel => "label the day, like \"Conference day\", empty to remove it",
+            Action::Search => "jump to a slot by name",
+            Action::SearchNext => "jump to the next slot matching the last search",
         }
     }
 }
 
+/// Characters rebound by the user in keys.toml.
+static KEY_OVERRIDES: OnceLock<HashMap<char, Action>> = OnceLock::new();
+
+/// The built-in key bindings, with the user's overrides replacing whatever those keys did before.
+fn keymap() -> Vec<(KeyCode, KeyModifiers, Action)> {
+    keymap_with(KEY_OVERRIDES.get())
+}
+
+fn keymap_with(overrides: Option<&HashMap<char, Action>>) -> Vec<(KeyCode, KeyModifiers, Action)> {
+    let is_overridden = |code: &KeyCode| match (code, overrides) {
+        (KeyCode::Char(c), Some(overrides)) => overrides.contains_key(c),
+        _ => false,
+    };
+
+    let mut out: Vec<(KeyCode, KeyModifiers, Action)> = KEYMAP
+        .iter()
+        .filter(|(code, mods, _)| *mods != KeyModifiers::NONE || !is_overridden(code))
+        .copied()
+        .collect();
+
+    let mut custom: Vec<(char, Action)> = overrides
+        .into_iter()
+        .flatten()
+        .map(|(c, action)| (*c, *action))
+        .collect();
+    custom.sort_by_key(|(c, _)| *c);
+    out.extend(
+        custom
+            .into_iter()
+            .map(|(c, action)| (KeyCode::Char(c), KeyModifiers::NONE, action)),
+    );
+
+    out
+}
+
+/// The key to mention for an action in hints, preferring a plain letter over keys like Insert.
+fn key_for(action: Action) -> Option<String> {
+    let bindings: Vec<_> = keymap()
+        .into_iter()
+        .filter(|(_, _, bound)| *bound == action)
+        .collect();
+    let (code, modifiers, _) = bindings
+        .iter()
+        .find(|(code, mods, _)| matches!(code, KeyCode::Char(_)) && *mods == KeyModifiers::NONE)
+        .or(bindings.first())?;
+
+    Some(key_name(*code, *modifiers))
+}
+
+fn key_name(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let key = match code {
+        KeyCode::Char(c) => c.to_string(),
+        other => other.to_string(),
+    };
+
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl-{key}")
+    } else {
+        key
+    }
+}
+
 impl App {
     fn clear_screen(&mut self) {
         execute!(&mut self.stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
@@ -243,88 +772,523 @@ impl App {
         execute!(self.stdout, MoveToColumn(0)).unwrap();
     }
 
-    fn get_user_input(&mut self, prompt: impl AsRef<str>) -> io::Result<String> {
+    /// Reads a line of input. Tab completes it from `completions`, if there are any.
+    fn get_user_input(
+        &mut self,
+        prompt: impl AsRef<str>,
+        completions: &[String],
+    ) -> io::Result<String> {
+        self.read_input(prompt, None, completions)
+    }
+
+    /// Reads a line of input, showing `error` above the prompt if given. Esc cancels, same as an empty line.
+    fn prompt_input(&mut self, prompt: impl AsRef<str>, error: Option<&str>) -> io::Result<String> {
+        self.read_input(prompt, error, &[])
+    }
+
+    fn read_input(
+        &mut self,
+        prompt: impl AsRef<str>,
+        error: Option<&str>,
+        completions: &[String],
+    ) -> io::Result<String> {
+        Ok(self
+            .read_line(prompt, error, completions)?
+            .unwrap_or_default())
+    }
+
+    /// Reads a line of input like [`Self::read_input`], but `None` when Esc cancels it, for prompts
+    /// where an empty line means something.
+    fn read_line(
+        &mut self,
+        prompt: impl AsRef<str>,
+        error: Option<&str>,
+        completions: &[String],
+    ) -> io::Result<Option<String>> {
         self.clear_screen();
+        if let Some(error) = error {
+            print_styled(&mut self.stdout, error, vec![], Some(Color::Red))?;
+            print!("\r\n");
+        }
         print!("{}: ", prompt.as_ref());
         self.flush();
         let mut input = String::new();
+        let mut completer = Completer::default();
 
         loop {
             if let Event::Key(event) = read()? {
+                if event.code != KeyCode::Tab {
+                    completer.reset();
+                }
+
                 match event.code {
+                    KeyCode::Tab => {
+                        if let Some(completed) = completer.tab(&input, completions) {
+                            let typed = input.chars().count() as u16;
+                            if typed > 0 {
+                                execute!(self.stdout, MoveLeft(typed))?;
+                            }
+                            execute!(self.stdout, Clear(ClearType::UntilNewLine))?;
+                            print!("{completed}");
+                            self.stdout.flush()?;
+                            input = completed;
+                        }
+                    }
                     KeyCode::Char(c) => {
                         input.push(c);
                         print!("{}", c);
                         self.stdout.flush()?;
                     }
-                    KeyCode::Backspace => {
-                        if input.pop().is_some() {
-                            execute!(self.stdout, MoveLeft(1))?;
-                            print!(" ");
-                            execute!(self.stdout, MoveLeft(1))?;
-                            self.stdout.flush()?;
-                        }
+                    KeyCode::Backspace if input.pop().is_some() => {
+                        execute!(self.stdout, MoveLeft(1))?;
+                        print!(" ");
+                        execute!(self.stdout, MoveLeft(1))?;
+                        self.stdout.flush()?;
                     }
                     KeyCode::Enter => {
                         println!();
                         break;
                     }
+                    KeyCode::Esc => return Ok(None),
                     _ => {}
                 }
             }
         }
-        Ok(input)
+        Ok(Some(input))
     }
 
-    fn get_int(&mut self, prompt: impl AsRef<str>) -> Option<u32> {
+    fn get_duration(&mut self, prompt: impl AsRef<str>) -> Option<Duration> {
+        let mut error = None;
         loop {
-            let s = self.get_user_input(&prompt).unwrap();
+            let s = self.prompt_input(&prompt, error).unwrap();
             if s.is_empty() {
                 return None;
             };
 
-            if let Ok(num) = s.parse::<u32>() {
-                return Some(num);
+            if let Some(dur) = parse_duration(&s) {
+                return Some(dur);
             }
+
+            error = Some("invalid length, expected 90, 1:30 or 1h30m");
         }
     }
 
-    fn get_naivetime(&mut self, prompt: impl AsRef<str>) -> Option<TimeSinceMidnight> {
+    /// A length that may be negative, like `-30` or `+1h`.
+    fn get_offset(&mut self, prompt: impl AsRef<str>) -> Option<Duration> {
+        let mut error = None;
         loop {
-            let s = self.get_user_input(&prompt).unwrap();
+            let s = self.prompt_input(&prompt, error).unwrap();
             if s.is_empty() {
                 return None;
             };
 
-            if let Ok(time) = NaiveTime::parse_from_str(&s, "%H:%M") {
-                let time = naive_to_timesincemidnight(time);
-                return Some(time);
+            if let Some(dur) = parse_offset(&s) {
+                return Some(dur);
             }
+
+            error = Some("invalid offset, expected +30, -1:30 or -1h30m");
         }
     }
 
-    pub fn start() -> Self {
-        let today = current_day();
-        let day: Saved<Day> = Saved::load_or_create(today);
-        day.write().slots_config.make_valid();
-        let mut days: HashMap<NaiveDate, Saved<Day>> = Default::default();
-        days.insert(today, day.clone());
+    fn confirm(&mut self, prompt: impl AsRef<str>) -> bool {
+        let s = self
+            .get_user_input(format!("{} (y/n)", prompt.as_ref()), &[])
+            .unwrap();
+        s.eq_ignore_ascii_case("y")
+    }
 
-        Self {
+    fn get_weight(&mut self, prompt: impl AsRef<str>) -> Option<f32> {
+        let mut error = None;
+        loop {
+            let s = self.prompt_input(&prompt, error).unwrap();
+            if s.is_empty() {
+                return None;
+            };
+
+            if let Ok(num) = s.parse::<f32>() {
+                if num.is_finite() && num >= 0. {
+                    return Some(num);
+                }
+            }
+
+            error = Some("invalid weight, expected a number of 0 or more");
+        }
+    }
+
+    fn get_date(&mut self, prompt: impl AsRef<str>) -> Option<NaiveDate> {
+        let mut error = None;
+        loop {
+            let s = self.prompt_input(&prompt, error).unwrap();
+            if s.is_empty() {
+                return None;
+            };
+
+            if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+                return Some(date);
+            }
+
+            error = Some("invalid date, expected YYYY-MM-DD");
+        }
+    }
+
+    fn get_relative_day(&mut self, prompt: impl AsRef<str>, from: NaiveDate) -> Option<NaiveDate> {
+        let mut error = None;
+        loop {
+            let s = self.prompt_input(&prompt, error).unwrap();
+            if s.is_empty() {
+                return None;
+            };
+
+            if let Some(date) = relative_day(from, &s) {
+                return Some(date);
+            }
+
+            error = Some("invalid offset, expected +7, -3 or today");
+        }
+    }
+
+    /// Categories used in the selected day or given a color, for completing a slot's category.
+    fn known_categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = self
+            .selected_day
+            .read()
+            .slots_config
+            .iter()
+            .filter_map(|slot| slot.category.clone())
+            .chain(
+                CATEGORY_COLORS
+                    .get()
+                    .into_iter()
+                    .flat_map(|colors| colors.keys().cloned()),
+            )
+            .collect();
+        categories.sort();
+        categories.dedup();
+        categories
+    }
+
+    /// Names used in the selected day and by saved activities, for completing a slot's name.
+    fn known_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .selected_day
+            .read()
+            .slots_config
+            .iter()
+            .map(|slot| slot.name.clone())
+            .chain(Act::load_all().into_iter().map(|act| act.name))
+            .filter(|name| !name.is_empty())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Lets the user choose a saved activity by number, or by name. Unknown names become new activities.
+    fn pick_act(&mut self) -> Option<Act> {
+        let mut acts = Act::load_all();
+        acts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut prompt = String::new();
+        for (i, act) in acts.iter().enumerate() {
+            prompt.push_str(&format!("{:>3}  {}\r\n", i + 1, act.name));
+        }
+        prompt.push_str("\r\nactivity number or new name");
+
+        let s = self.get_user_input(prompt, &[]).unwrap();
+        if s.is_empty() {
+            return None;
+        }
+
+        if let Some(act) = s
+            .parse::<usize>()
+            .ok()
+            .and_then(|num| acts.get(num.wrapping_sub(1)))
+        {
+            return Some(act.clone());
+        }
+
+        if let Some(act) = acts.iter().find(|act| act.name.eq_ignore_ascii_case(&s)) {
+            return Some(act.clone());
+        }
+
+        let act = Act {
+            name: s,
+            id: Uuid::new_v4(),
+        };
+        act.save().unwrap();
+        Some(act)
+    }
+
+    fn pick_template(&mut self, templates: &[Template]) -> Option<Template> {
+        let mut prompt = String::new();
+        for (i, template) in templates.iter().enumerate() {
+            prompt.push_str(&format!("{:>3}  {}\r\n", i + 1, template.name));
+        }
+        prompt.push_str("\r\ntemplate number or name");
+
+        let names: Vec<String> = templates
+            .iter()
+            .map(|template| template.name.clone())
+            .collect();
+        let s = self.get_user_input(prompt, &names).unwrap();
+        if s.is_empty() {
+            return None;
+        }
+
+        s.parse::<usize>()
+            .ok()
+            .and_then(|num| templates.get(num.wrapping_sub(1)))
+            .or_else(|| {
+                templates
+                    .iter()
+                    .find(|template| template.name.eq_ignore_ascii_case(&s))
+            })
+            .cloned()
+    }
+
+    /// A time given as `HH:MM`. Asks again while `check` finds fault with it.
+    fn get_naivetime(
+        &mut self,
+        prompt: impl AsRef<str>,
+        check: impl Fn(TimeSinceMidnight) -> Result<(), String>,
+    ) -> Option<TimeSinceMidnight> {
+        let mut error = None;
+        loop {
+            let s = self.prompt_input(&prompt, error.as_deref()).unwrap();
+            if s.is_empty() {
+                return None;
+            };
+
+            error = match NaiveTime::parse_from_str(&s, "%H:%M") {
+                Ok(time) => {
+                    let time = naive_to_timesincemidnight(time);
+                    match check(time) {
+                        Ok(()) => return Some(time),
+                        Err(e) => Some(e),
+                    }
+                }
+                Err(_) => Some("invalid time, expected HH:MM".to_string()),
+            };
+        }
+    }
+
+    /// A start time, and whether it's soft, i.e. given as `~HH:MM`.
+    /// Asks again while `check` finds fault with a fixed start.
+    fn get_start(
+        &mut self,
+        prompt: impl AsRef<str>,
+        check: impl Fn(TimeSinceMidnight) -> Result<(), &'static str>,
+    ) -> Option<(TimeSinceMidnight, bool)> {
+        let mut error = None;
+        loop {
+            let s = self.prompt_input(&prompt, error).unwrap();
+            if s.is_empty() {
+                return None;
+            };
+
+            error = match parse_start(&s, current_time()) {
+                Some((time, false)) => match check(time) {
+                    Ok(()) => return Some((time, false)),
+                    Err(e) => Some(e),
+                },
+                Some(soft) => return Some(soft),
+                None => {
+                    Some("invalid time, expected HH:MM or +30, with ~ in front for a soft start")
+                }
+            };
+        }
+    }
+
+    pub fn start(args: Args) -> Self {
+        let selected = args.date.unwrap_or_else(current_day);
+        let day = LoadedDay::load_or_create(selected);
+        let mut notice = None;
+        let home_dir = args
+            .home_dir
+            .or_else(dirs::home_dir)
+            .or_else(|| std::env::var_os("XDG_STATE_HOME").map(PathBuf::from));
+        if home_dir.is_none() {
+            notice = Some("no home directory found, use --home-dir to write files".to_string());
+        }
+        if !args.readonly {
+            let dropped = day.write().slots_config.make_valid();
+            if !dropped.is_empty() {
+                day.mark_dirty();
+                let slots: Vec<String> = dropped.iter().map(|idx| (idx + 1).to_string()).collect();
+                notice = Some(format!(
+                    "dropped out of order start of slot {}",
+                    slots.join(", ")
+                ));
+            }
+        }
+        if let Some(corrupt) = corrupt_days_notice() {
+            notice = Some(corrupt);
+        }
+        let mut days: HashMap<NaiveDate, LoadedDay> = Default::default();
+        days.insert(selected, day.clone());
+
+        // Notifications follow today's plan even when another day is opened.
+        days.entry(current_day())
+            .or_insert_with(|| LoadedDay::load_or_create(current_day()));
+
+        let mut app = Self {
             stdout: io::stdout(),
             selected_day: day,
             days,
             cursor: Cursor::default(),
-        }
+            undo_stack: vec![],
+            redo_stack: vec![],
+            warn_before: args.warn_before,
+            warned_slots: Default::default(),
+            readonly: args.readonly,
+            last_search: None,
+            notify: !args.no_notify,
+            summary: !args.no_summary,
+            summarized: None,
+            show_diagnostics: false,
+            show_allocation: false,
+            ascii: args.ascii,
+            compact: args.compact,
+            title: args.title,
+            name_width: args.name_width,
+            notice,
+            round: args.round,
+            home_dir,
+            scroll_offset: 0,
+            visible_rows: 1,
+            visual_anchor: None,
+            slot_since: now(),
+        };
+        app.restore_cursor();
+        app
     }
 
     pub fn load_or_create(&mut self, dayte: NaiveDate) {
-        if let Some(day) = self.days.get(&dayte).cloned() {
-            self.selected_day = day;
-        } else {
-            let day = Saved::load_or_create(dayte);
-            self.days.insert(dayte, day.clone());
-            self.selected_day = day;
+        self.save_cursor();
+        self.visual_anchor = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+
+        if let Err(e) = self.selected_day.flush() {
+            self.notice = Some(e);
+        }
+        self.selected_day = self.get_day(dayte);
+        self.restore_cursor();
+    }
+
+    fn get_day(&mut self, dayte: NaiveDate) -> LoadedDay {
+        let day = self
+            .days
+            .entry(dayte)
+            .or_insert_with(|| LoadedDay::load_or_create(dayte))
+            .clone();
+        if let Some(notice) = corrupt_days_notice() {
+            self.notice = Some(notice);
+        }
+        day
+    }
+
+    /// Writes every loaded day that changed since it was last written.
+    pub fn flush_all(&mut self) {
+        for day in self.days.values() {
+            if let Err(e) = day.flush() {
+                self.notice = Some(e);
+            }
+        }
+    }
+
+    fn save_cursor(&self) {
+        let state = DayCursor {
+            day: self.selected_day.read().day,
+            cursor: self.cursor,
+        };
+        state.save().unwrap();
+    }
+
+    /// Puts the cursor back where it was last left on the selected day, if it has been visited before,
+    /// and keeps it within the day's slots either way.
+    fn restore_cursor(&mut self) {
+        let day = self.selected_day.read();
+        if let Some(state) = DayCursor::load(day.day) {
+            self.cursor = state.cursor;
+        }
+        self.cursor.index = self
+            .cursor
+            .index
+            .min(day.slots_config.len().saturating_sub(1));
+    }
+
+    fn hidden_fields(&self) -> Vec<Field> {
+        hidden_fields(&self.selected_day.read().slots(), self.compact)
+    }
+
+    /// Applies a mutation to the selected day, recording the previous slots for undo if anything changed.
+    /// A mutation that moves a fixed start outside the plan window is undone.
+    fn edit_day(&mut self, f: impl FnOnce(&mut Day)) {
+        let (before, result) = edit_slots(&self.selected_day, f);
+
+        if let Some(before) = before {
+            if self.undo_stack.len() == UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+            self.undo_stack.push(before);
+            self.redo_stack.clear();
+        }
+
+        if let Err(idx) = result {
+            self.notice = Some(format!("slot {} would start outside the day", idx + 1));
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(prev) = self.undo_stack.pop() {
+            let mut day = write_day(&self.selected_day);
+            let current = mem::replace(&mut day.slots_config, prev);
+            self.redo_stack.push(current);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.redo_stack.pop() {
+            let mut day = write_day(&self.selected_day);
+            let current = mem::replace(&mut day.slots_config, next);
+            self.undo_stack.push(current);
+        }
+    }
+
+    fn day_json_path(&self) -> Option<PathBuf> {
+        let day = self.selected_day.read().day;
+        Some(self.home_dir.as_ref()?.join(format!("dagplan-{day}.json")))
+    }
+
+    /// Writes the selected day's slot configuration to `path`.
+    pub fn export_day_json(&self, path: &Path) -> io::Result<()> {
+        write_slots_json(path, &self.selected_day.read().slots_config)
+    }
+
+    /// Replaces the selected day's slots with the ones in `path`.
+    ///
+    /// Files that break the start-time ordering are rejected as a whole.
+    pub fn import_day_json(&mut self, path: &Path) -> io::Result<()> {
+        let slots = read_slots_json(path)?;
+        self.edit_day(|day| day.slots_config = slots);
+        Ok(())
+    }
+
+    /// Moves the cursor to the first slot from `from` onwards, wrapping around, whose name contains the last search.
+    fn jump_to_match(&mut self, from: usize) {
+        let Some(query) = &self.last_search else {
+            return;
+        };
+
+        let day = self.selected_day.read();
+        let slots = &day.slots_config;
+        let found = (0..slots.len())
+            .map(|offset| (from + offset) % slots.len())
+            .find(|idx| slots[*idx].name.to_lowercase().contains(query.as_str()));
+
+        if let Some(idx) = found {
+            self.cursor.index = idx;
         }
     }
 
@@ -337,14 +1301,42 @@ impl App {
         Some(self.cursor.index.clamp(0, slots.len() - 1))
     }
 
+    /// The slots selected in visual mode, from the anchor to the cursor.
+    fn selection(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let cursor = self.current_index()?;
+        let last = self.selected_day.read().slots_config.len() - 1;
+        Some(anchor.min(cursor)..=anchor.max(cursor).min(last))
+    }
+
     fn handle_action(&mut self, action: Action) -> ControlFlow<()> {
+        self.notice = None;
+
+        if self.readonly && action.is_mutating() {
+            return ControlFlow::Continue(());
+        }
+
         match action {
             Action::Down => self
                 .cursor
                 .down(self.selected_day.read().slots_config.len()),
             Action::Up => self.cursor.up(),
-            Action::Left => self.cursor.left(),
-            Action::Right => self.cursor.right(),
+            Action::PageDown => {
+                let len = self.selected_day.read().slots_config.len();
+                self.cursor.index =
+                    (self.cursor.index + self.visible_rows).min(len.saturating_sub(1));
+            }
+            Action::PageUp => {
+                self.cursor.index = self.cursor.index.saturating_sub(self.visible_rows);
+            }
+            Action::Left => {
+                let hidden = self.hidden_fields();
+                self.cursor.left(&hidden);
+            }
+            Action::Right => {
+                let hidden = self.hidden_fields();
+                self.cursor.right(&hidden);
+            }
             Action::Tomorrow => {
                 let next_day = self.selected_day.read().day.succ_opt().unwrap();
                 self.load_or_create(next_day);
@@ -353,15 +1345,101 @@ impl App {
                 let prev_day = self.selected_day.read().day.pred_opt().unwrap();
                 self.load_or_create(prev_day);
             }
-            Action::Insert => {
-                self.selected_day.write().insert(self.cursor);
+            Action::Today => self.load_or_create(current_day()),
+            Action::GotoDate => {
+                if let Some(date) = self.get_date("go to date (YYYY-MM-DD)") {
+                    self.load_or_create(date);
+                }
+            }
+            Action::JumpDays => {
+                let from = self.selected_day.read().day;
+                if let Some(date) = self.get_relative_day("days (+7, -3 or today)", from) {
+                    self.load_or_create(date);
+                }
+            }
+            Action::Insert => {
+                let mut index = self.cursor.index;
+                self.edit_day(|day| index = day.insert(index, slot_defaults()));
+                self.cursor.index = index;
+            }
+            Action::InsertBelow => {
+                let mut index = self.current_index().map_or(0, |idx| idx + 1);
+                self.edit_day(|day| index = day.insert(index, slot_defaults()));
+                self.cursor.index = index;
+            }
+            Action::InsertTemplate => {
+                let Some(path) = template::templates_path() else {
+                    return ControlFlow::Continue(());
+                };
+                let templates = match template::load_templates(&path) {
+                    Ok(templates) if templates.is_empty() => {
+                        self.notice = Some(format!("no templates in {}", path.display()));
+                        return ControlFlow::Continue(());
+                    }
+                    Ok(templates) => templates,
+                    Err(e) => {
+                        self.notice = Some(e);
+                        return ControlFlow::Continue(());
+                    }
+                };
+
+                if let Some(template) = self.pick_template(&templates) {
+                    let index = self.cursor.index;
+                    self.edit_day(|day| {
+                        template::insert_template(&mut day.slots_config, index, &template);
+                    });
+                }
             }
             Action::Delete => {
                 if let Some(idx) = self.current_index() {
-                    self.selected_day.write().slots_config.remove(idx);
+                    self.edit_day(|day| day.slots_config.remove(idx));
+                }
+            }
+            Action::ClearDay => {
+                if !self.selected_day.read().slots_config.is_empty()
+                    && self.confirm("delete all slots of this day?")
+                {
+                    self.edit_day(|day| day.slots_config.clear());
+                }
+            }
+            Action::CopyPrevious => {
+                let day = self.selected_day.read().day;
+                let Some(prev) = day.pred_opt().and_then(Saved::<Day>::load) else {
+                    return ControlFlow::Continue(());
+                };
+
+                if !self.selected_day.read().slots_config.is_empty()
+                    && !self.confirm("replace this day's slots with the previous day's?")
+                {
+                    return ControlFlow::Continue(());
                 }
+
+                let slots = prev.read().slots_config.without_progress();
+                self.edit_day(|day| day.slots_config = slots);
+            }
+            Action::Quit if self.visual_anchor.is_some() => self.visual_anchor = None,
+            Action::Quit => {
+                self.save_cursor();
+                return ControlFlow::Break(());
+            }
+            Action::Visual => {
+                self.visual_anchor = match self.visual_anchor {
+                    Some(_) => None,
+                    None => self.current_index(),
+                };
+            }
+            Action::Edit if self.visual_anchor.is_some() => {
+                let Some(selection) = self.selection() else {
+                    return ControlFlow::Continue(());
+                };
+                let prompt = format!("length of {} slots", selection.clone().count());
+                let Some(length) = self.get_duration(prompt) else {
+                    return ControlFlow::Continue(());
+                };
+
+                self.edit_day(|day| set_lengths(&mut day.slots_config, selection, length));
+                self.visual_anchor = None;
             }
-            Action::Quit => return ControlFlow::Break(()),
             Action::Edit => {
                 let slots = self.selected_day.read().slots_config.clone();
                 if slots.is_empty() {
@@ -372,33 +1450,83 @@ impl App {
                 let mut selected_slot = slots.get(idx).unwrap().clone();
                 match self.cursor.field {
                     Field::Name => {
-                        let name = self.get_user_input("activity name").unwrap();
+                        let names = self.known_names();
+                        let name = self.get_user_input("activity name", &names).unwrap();
+                        if name.is_empty() {
+                            return ControlFlow::Continue(());
+                        }
                         selected_slot.name = name;
                     }
                     Field::Length => {
                         selected_slot.config.fixed_length = !selected_slot.config.fixed_length;
                     }
                     Field::Start => {
-                        if selected_slot.config.start.is_some() {
-                            selected_slot.config.start = None;
+                        let config = &mut selected_slot.config;
+                        if config.start.is_some() || config.soft_start.is_some() {
+                            config.start = None;
+                            config.soft_start = None;
                         } else {
-                            if let Some(time) = self.get_naivetime("set starttime") {
-                                selected_slot.config.start = Some(time);
-                            } else {
+                            let window = self.selected_day.read().window();
+                            let check = |start| {
+                                let mut slots = slots.to_vec();
+                                slots[idx].config.start = Some(start);
+                                if SlotDtos::validate(&slots).is_err() {
+                                    Err("start is out of order with the other starts")
+                                } else if SlotDtos::validate_within(
+                                    &slots,
+                                    window.start,
+                                    window.total,
+                                )
+                                .is_err()
+                                {
+                                    Err("start is outside the day")
+                                } else {
+                                    Ok(())
+                                }
+                            };
+                            let Some((time, soft)) =
+                                self.get_start("set starttime, ~HH:MM for a soft start", check)
+                            else {
                                 return ControlFlow::Continue(());
+                            };
+                            if soft {
+                                selected_slot.config.soft_start = Some(time);
+                            } else {
+                                selected_slot.config.start = Some(time);
                             }
                         }
                     }
-                    Field::Requested => match self.get_int("length in minutes") {
-                        Some(num) => selected_slot.config.length = Duration::minutes(num as i64),
+                    Field::End => {
+                        let start = selected_slot
+                            .config
+                            .start
+                            .unwrap_or(self.selected_day.read().slots()[idx].start);
+
+                        let check = |end| {
+                            if end > start {
+                                Ok(())
+                            } else {
+                                Err(format!("end must be after {}", format_naive(start)))
+                            }
+                        };
+                        let Some(end) = self.get_naivetime("set endtime", check) else {
+                            return ControlFlow::Continue(());
+                        };
+
+                        selected_slot.config.length = end - start;
+                        selected_slot.config.fixed_length = true;
+                    }
+                    Field::Requested => match self.get_duration("length (90, 1:30 or 1h30m)") {
+                        Some(length) => selected_slot.config.length = length,
+                        None => return ControlFlow::Continue(()),
+                    },
+                    Field::Weight => match self.get_weight("elastic weight") {
+                        Some(weight) => selected_slot.config.weight = weight,
                         None => return ControlFlow::Continue(()),
                     },
                 }
 
-                self.selected_day
-                    .write()
-                    .slots_config
-                    .over_ride(idx, selected_slot);
+                self.edit_day(|day| day.slots_config.over_ride(idx, selected_slot));
             }
             Action::Upswap => {
                 let mut slots = self.selected_day.read().slots_config.clone();
@@ -413,7 +1541,7 @@ impl App {
                     && !(idx == 1 && slots.get(idx - 1).unwrap().config.start.is_some())
                 {
                     slots.swap(idx, idx - 1);
-                    self.selected_day.write().slots_config.swap(idx, idx - 1);
+                    self.edit_day(|day| day.slots_config.swap(idx, idx - 1));
                     self.cursor.up();
                 }
             }
@@ -425,7 +1553,7 @@ impl App {
 
                 let idx = self.cursor.index.clamp(0, slots.len() - 1);
 
-                self.selected_day.write().slots_config.swap(idx, idx + 1);
+                self.edit_day(|day| day.slots_config.swap(idx, idx + 1));
                 self.cursor
                     .down(self.selected_day.read().slots_config.len());
             }
@@ -436,10 +1564,307 @@ impl App {
                 };
 
                 let idx = self.cursor.index.clamp(0, slots.len() - 1);
-                self.selected_day
-                    .write()
-                    .slots_config
-                    .set_start(idx, current_time());
+                self.edit_day(|day| day.slots_config.set_start(idx, current_time()));
+            }
+            Action::BeginCascade => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                self.edit_day(|day| day.slots_config.set_start_cascade(idx, current_time()));
+            }
+            Action::WeekView => self.week_view(),
+            Action::ToggleDiagnostics => self.show_diagnostics = !self.show_diagnostics,
+            Action::ToggleAllocation => self.show_allocation = !self.show_allocation,
+            Action::Search => {
+                let query = self.get_user_input("search", &[]).unwrap();
+                if query.is_empty() {
+                    return ControlFlow::Continue(());
+                }
+
+                self.last_search = Some(query.to_lowercase());
+                self.jump_to_match(0);
+            }
+            Action::SearchNext => self.jump_to_match(self.cursor.index + 1),
+            Action::PickAct => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let Some(act) = self.pick_act() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.act = Some(act.id);
+                slot.name = act.name;
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::EditNote => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let note = self.get_user_input("note", &[]).unwrap();
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.note = (!note.is_empty()).then_some(note);
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::SetCategory => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let categories = self.known_categories();
+                let category = self.get_user_input("category", &categories).unwrap();
+                let category = category.trim();
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.category = (!category.is_empty()).then(|| category.to_string());
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::FillToNext => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let (results, window) = {
+                    let day = self.selected_day.read();
+                    (day.slots(), day.window())
+                };
+                let window_start = results[0].configured.config.start.unwrap_or(window.start);
+                let length = fill_length(&results, idx, window_start + window.total);
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.config.length = length;
+                slot.config.fixed_length = true;
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::ToggleFrozen => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let results = self.selected_day.read().slots();
+                let block = results[idx].block;
+                self.edit_day(|day| day.slots_config.toggle_frozen(&results, block));
+            }
+            Action::ToggleFixedAsScheduled => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let results = self.selected_day.read().slots();
+                self.edit_day(|day| day.slots_config.toggle_fixed_as_scheduled(idx, &results));
+            }
+            Action::AdoptLengths => {
+                let results = self.selected_day.read().slots();
+                self.edit_day(|day| day.slots_config.adopt_lengths(&results));
+            }
+            Action::EvenOut => {
+                let results = self.selected_day.read().slots();
+                self.edit_day(|day| day.slots_config.even_out(&results));
+            }
+            Action::BorrowFromNext => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+                let Some(by) = self.get_duration("take from the next slot (90, 1:30 or 1h30m)")
+                else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut result = Ok(());
+                self.edit_day(|day| result = day.slots_config.borrow_from_next(idx, by));
+                if let Err(next) = result {
+                    let len = self.selected_day.read().slots_config.len();
+                    self.notice = Some(if next < len {
+                        format!("slot {} isn't that long", next + 1)
+                    } else {
+                        "no slot below to take from".to_string()
+                    });
+                }
+            }
+            Action::CycleRecurrence => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.done = false;
+                let name = slot.name.clone();
+                let existing = Recurring::load_all()
+                    .into_iter()
+                    .find(|recurring| recurring.slot.name == name);
+
+                let status = match existing {
+                    None => {
+                        let recurring = Recurring {
+                            id: Uuid::new_v4(),
+                            slot,
+                            every: Recurrence::Daily,
+                        };
+                        recurring.save().unwrap();
+                        "recurs daily"
+                    }
+                    Some(mut recurring) if recurring.every == Recurrence::Daily => {
+                        recurring.slot = slot;
+                        recurring.every = Recurrence::Weekdays;
+                        recurring.save().unwrap();
+                        "recurs on weekdays"
+                    }
+                    Some(recurring) => {
+                        Recurring::delete(recurring.id);
+                        "no longer recurs"
+                    }
+                };
+                self.notice = Some(format!("{name} {status}"));
+            }
+            Action::ShiftStarts => {
+                let Some(by) = self.get_offset("shift starts by") else {
+                    return ControlFlow::Continue(());
+                };
+
+                let window = self.selected_day.read().window();
+                let mut result = Ok(());
+                self.edit_day(|day| {
+                    result = day
+                        .slots_config
+                        .shift_starts(by, window.start, window.total)
+                });
+                if let Err(idx) = result {
+                    self.notice = Some(format!("slot {} would start outside the day", idx + 1));
+                }
+            }
+            Action::AddBanner => {
+                let banner = self.get_user_input("all-day note", &[]).unwrap();
+                write_day(&self.selected_day).add_banner(&banner);
+            }
+            Action::RemoveBanner => {
+                let banners = self.selected_day.read().banners.clone();
+                if banners.is_empty() {
+                    self.notice = Some("no all-day notes to remove".to_string());
+                    return ControlFlow::Continue(());
+                }
+
+                let mut prompt = String::new();
+                for (i, banner) in banners.iter().enumerate() {
+                    prompt.push_str(&format!("{:>3}  {banner}\r\n", i + 1));
+                }
+                prompt.push_str("\r\nnumber to remove");
+
+                let s = self.get_user_input(prompt, &[]).unwrap();
+                if let Ok(num) = s.parse::<usize>() {
+                    write_day(&self.selected_day).remove_banner(num.wrapping_sub(1));
+                }
+            }
+            Action::SetLabel => {
+                let Some(label) = self
+                    .read_line("day label, empty to remove it", None, &[])
+                    .unwrap()
+                else {
+                    return ControlFlow::Continue(());
+                };
+                write_day(&self.selected_day).set_label(&label);
+            }
+            Action::Duplicate => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                self.edit_day(|day| duplicate(&mut day.slots_config, idx));
+            }
+            Action::Split => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                self.edit_day(|day| day.slots_config.split(idx));
+            }
+            Action::Merge => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                self.edit_day(|day| day.slots_config.merge_next(idx));
+            }
+            Action::ToggleDone => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.done = !slot.done;
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::JumpToNow => {
+                let day = self.selected_day.read();
+                if day.day == current_day() {
+                    if let Some(idx) = active_index(&day.slots(), current_time()) {
+                        self.cursor.index = idx;
+                    }
+                }
+            }
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Help => {
+                self.draw_help();
+                // Only a key closes it. Resizing redraws it for the new size.
+                loop {
+                    match read() {
+                        Ok(Event::Key(_)) | Err(_) => break,
+                        Ok(Event::Resize(..)) => self.draw_help(),
+                        Ok(_) => {}
+                    }
+                }
+            }
+            Action::ExportIcal => {
+                let Some(home_dir) = &self.home_dir else {
+                    return ControlFlow::Continue(());
+                };
+                let day = self.selected_day.read();
+                let ical = export::to_ical(day.day, &day.slots());
+                let path = home_dir.join(format!("dagplan-{}.ics", day.day));
+                if let Err(e) = std::fs::write(&path, ical) {
+                    self.notice = Some(format!("{}: {e}", path.display()));
+                }
+            }
+            Action::ExportJson => {
+                if let Some(path) = self.day_json_path() {
+                    if let Err(e) = self.export_day_json(&path) {
+                        self.notice = Some(format!("{}: {e}", path.display()));
+                    }
+                }
+            }
+            Action::ImportJson => {
+                if let Some(path) = self.day_json_path() {
+                    if let Err(e) = self.import_day_json(&path) {
+                        self.notice = Some(format!("{}: {e}", path.display()));
+                    }
+                }
+            }
+            Action::ToggleBreak => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.kind = match slot.kind {
+                    SlotKind::Break => SlotKind::Activity,
+                    SlotKind::Activity | SlotKind::Filler => SlotKind::Break,
+                };
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
+            }
+            Action::ToggleFiller => {
+                let Some(idx) = self.current_index() else {
+                    return ControlFlow::Continue(());
+                };
+
+                let mut slot = self.selected_day.read().slots_config[idx].clone();
+                slot.kind = match slot.kind {
+                    SlotKind::Filler => SlotKind::Activity,
+                    SlotKind::Activity | SlotKind::Break => SlotKind::Filler,
+                };
+                self.edit_day(|day| day.slots_config.over_ride(idx, slot));
             }
         }
 
@@ -448,172 +1873,717 @@ impl App {
 
     fn draw(&mut self) {
         self.clear_screen();
-        println!("{}", self.selected_day.read().day);
+        let (width, height) = terminal_size();
+        // Lines other than slot rows: the date, planned time and the legend below the slots.
+        let mut chrome = 4;
+        print!("{}", self.selected_day.read().day);
+        if let Some(label) = self.selected_day.read().label.clone() {
+            print!("  ");
+            print_styled(&mut self.stdout, &label, vec![Attribute::Bold], None).unwrap();
+        }
+        if self.readonly {
+            print!(" [READONLY]");
+        }
+        println!();
         self.left_cursor();
+        let banners = self.selected_day.read().banners.join(" · ");
+        if !banners.is_empty() {
+            chrome += 1;
+            print_styled(&mut self.stdout, &banners, vec![], Some(Color::Magenta)).unwrap();
+            println!();
+            self.left_cursor();
+        }
+        if let Some(notice) = self.notice.clone() {
+            chrome += 1;
+            print_styled(&mut self.stdout, &notice, vec![], Some(Color::Red)).unwrap();
+            println!();
+            self.left_cursor();
+        }
+        let selection = self.selection();
+        if let Some(selection) = &selection {
+            chrome += 1;
+            let line = format!(
+                "-- VISUAL -- {} selected, enter sets their length",
+                selection.clone().count()
+            );
+            print_styled(&mut self.stdout, &line, vec![Attribute::Bold], None).unwrap();
+            println!();
+            self.left_cursor();
+        }
         let slots = self.selected_day.read().slots();
+        let window = self.selected_day.read().window();
+        if let Some(over) = overrun(&slots, window) {
+            chrome += 1;
+            let s = format!("OVERRUN by {}m", over.num_minutes());
+            print_styled(
+                &mut self.stdout,
+                &s,
+                vec![Attribute::Bold],
+                Some(Color::Red),
+            )
+            .unwrap();
+            println!();
+            self.left_cursor();
+        }
+        self.draw_planned();
+        let conflicts = SlotDtos::out_of_order_starts(&self.selected_day.read().slots_config);
         if slots.is_empty() {
-            print!("empty...");
+            match key_for(Action::Insert) {
+                Some(key) => print!("empty — press '{key}' to insert a slot"),
+                None => print!("empty"),
+            }
+            self.flush();
             return;
         }
         let index = self.cursor.index.clamp(0, slots.len() - 1);
 
         let current_time = current_time();
+        let is_today = self.selected_day.read().day == current_day();
+        let active = if is_today {
+            active_index(&slots, current_time)
+        } else {
+            None
+        };
+        // When now isn't within any slot, a line marks where it falls between them.
+        let mut show_now_line = is_today && active.is_none();
+
+        if show_now_line {
+            chrome += 1;
+        }
+        let totals = category_totals(&slots);
+        if !totals.is_empty() {
+            chrome += 1;
+        }
+        let allocation = self.show_allocation.then(|| {
+            let day = self.selected_day.read();
+            let window = day.window();
+            let block = slots[index].block;
+            let allocations =
+                slot::block_allocations(window.start, window.total, day.slots_config.to_vec());
+            format!("block {}: {:?}", block + 1, allocations[block])
+        });
+        if allocation.is_some() {
+            chrome += 1;
+        }
+        if self.show_diagnostics {
+            chrome += 2 + self.selected_day.read().diagnostics().len().max(1);
+        }
+        let rows = height.saturating_sub(chrome).max(1);
+        let offset = scroll_into_view(&slots, self.scroll_offset, index, rows);
+        self.scroll_offset = offset;
+        self.visible_rows = rows;
+        if offset > 0 && current_time < slots[offset].start {
+            // Now is above the visible slots.
+            show_now_line = false;
+        }
+        let mut used = 0;
+
+        let hidden = hidden_fields(&slots, self.compact);
+        if hidden.contains(&self.cursor.field) {
+            self.cursor.left(&hidden);
+        }
 
         let max_name_len: usize = slots
             .iter()
             .map(|slot| slot.configured.name.chars().count())
             .max()
             .unwrap_or_default();
-        let name_width = max_name_len.max(15);
+        let name_width = if let Some(width) = self.name_width {
+            width
+        } else if self.compact {
+            max_name_len.clamp(4, COMPACT_NAME_WIDTH)
+        } else {
+            max_name_len.max(15)
+        };
+        let name_width = fit_name_width(name_width, row_width_without_name(&hidden), width);
+
+        for (i, slot) in slots.iter().enumerate().skip(offset) {
+            let separated = i > offset && slots[i - 1].block != slot.block;
+            used += slot_rows(slot) + usize::from(separated);
+            if used > rows {
+                // Now is below the visible slots.
+                show_now_line = false;
+                break;
+            }
+
+            if show_now_line && current_time < slot.start {
+                self.draw_now_line(current_time);
+                show_now_line = false;
+            }
+
+            if separated {
+                print_styled(&mut self.stdout, "────", vec![Attribute::Dim], None).unwrap();
+                println!();
+                self.left_cursor();
+            }
+
+            print!("{} ", schedule_glyph(&slot.configured.config, self.ascii));
+            for field in Field::ALL {
+                if hidden.contains(&field) {
+                    continue;
+                }
 
-        for (i, slot) in slots.iter().enumerate() {
-            for field in [Field::Name, Field::Start, Field::Requested, Field::Length] {
                 let s = match field {
                     Field::Name => format!(
                         "{:width$}",
-                        slot.configured.name.clone(),
+                        truncate(&slot.configured.name, name_width),
                         width = name_width
                     ),
                     Field::Length => format_dur(slot.length),
-                    Field::Start => format_naive(slot.start),
+                    Field::Start => format_naive(round_time(slot.start, self.round)),
+                    Field::End => format_naive(round_time(slot.start + slot.length, self.round)),
                     Field::Requested => format_dur(slot.configured.config.length),
+                    Field::Weight => format!("w{:<4.1}", slot.configured.config.weight),
                 };
 
                 let mut attrs = vec![];
-                if self.cursor.field == field && i == index {
+                let selected = selection.as_ref().is_some_and(|range| range.contains(&i));
+                if selected || (self.cursor.field == field && i == index) {
                     attrs.push(Attribute::Reverse);
                 }
 
+                match slot.configured.kind {
+                    SlotKind::Break => attrs.push(Attribute::Dim),
+                    SlotKind::Filler => attrs.push(Attribute::Italic),
+                    SlotKind::Activity => {}
+                }
+
                 if (field == Field::Start && slot.configured.config.start.is_some())
                     || (field == Field::Requested && slot.configured.config.fixed_length)
                 {
                     attrs.push(Attribute::Bold);
+                } else if field == Field::Start && slot.configured.config.soft_start.is_some() {
+                    attrs.push(Attribute::Underlined);
+                }
+
+                let mut color = warning_color(&slot.warning);
+                if field == Field::Name && color.is_none() {
+                    color = category_color(slot.configured.category.as_deref());
+                }
+                let conflict = field == Field::Start && conflicts.contains(&i);
+                if conflict {
+                    color = Some(Color::Red);
+                }
+
+                if slot.configured.done {
+                    attrs.push(Attribute::CrossedOut);
+                    color = Some(Color::DarkGrey);
+                }
+
+                print_styled(&mut self.stdout, &s, attrs, color).unwrap();
+
+                if field == Field::Length && !hidden.contains(&Field::Requested) {
+                    let delta = length_delta(slot).unwrap_or_default();
+                    print!(" ");
+                    print_styled(
+                        &mut self.stdout,
+                        &format!("{delta:<8}"),
+                        vec![Attribute::Dim],
+                        None,
+                    )
+                    .unwrap();
+                }
+
+                if conflict {
+                    print_styled(&mut self.stdout, "!", vec![], Some(Color::Red)).unwrap();
+                    print!("  ");
+                } else {
+                    print!("   ");
                 }
+            }
+
+            if active == Some(i) {
+                let clock = active_marker(current_time, self.ascii);
+                let progress = progress_bar(slot, current_time);
+                print!("{clock} {progress}");
+            }
+
+            println!();
+            self.left_cursor();
 
-                print_styled(&mut self.stdout, &s, attrs).unwrap();
-                print!("   ");
+            if let Some(note) = &slot.configured.note {
+                print_styled(
+                    &mut self.stdout,
+                    &format!("  {note}"),
+                    vec![Attribute::Dim],
+                    None,
+                )
+                .unwrap();
+                println!();
+                self.left_cursor();
             }
+        }
+
+        if show_now_line {
+            self.draw_now_line(current_time);
+        }
 
-            if self.selected_day.read().day == current_day()
-                && slot.start < current_time
-                && (slot.start + slot.length) > current_time
-            {
-                let clock = clock_emoji(current_time);
-                print!("{clock}");
+        println!();
+        self.left_cursor();
+        print_styled(
+            &mut self.stdout,
+            "red",
+            vec![],
+            warning_color(&Err(ScheduleError::InsufficientFixedTime)),
+        )
+        .unwrap();
+        print!(": fixed slots don't fit   ");
+        print_styled(
+            &mut self.stdout,
+            "yellow",
+            vec![],
+            warning_color(&Err(ScheduleError::NoElasticSlots)),
+        )
+        .unwrap();
+        print!(": fixed slots stretched, no elastic slots");
+
+        if !totals.is_empty() {
+            println!();
+            self.left_cursor();
+            for (i, (category, total)) in totals.iter().enumerate() {
+                if i > 0 {
+                    print!(" · ");
+                }
+                let color = category_color(Some(category));
+                print_styled(&mut self.stdout, category, vec![], color).unwrap();
+                print!(" {}", format_dur(*total).trim());
             }
+        }
 
+        if let Some(allocation) = allocation {
             println!();
             self.left_cursor();
+            print_styled(&mut self.stdout, &allocation, vec![Attribute::Dim], None).unwrap();
+        }
+
+        if self.show_diagnostics {
+            self.draw_diagnostics();
         }
 
         self.flush();
     }
 
-    fn current_slot(&self) -> Option<SlotResult> {
-        let slots = self.days.get(&current_day())?.read().slots();
-        let now = current_time();
-
-        for slot in slots.iter() {
-            if slot.start < now && (slot.start + slot.length) > now {
-                return Some(slot.clone());
-            }
+    fn draw_diagnostics(&mut self) {
+        let diagnostics = self.selected_day.read().diagnostics();
+        println!();
+        self.left_cursor();
+        println!();
+        self.left_cursor();
+        if diagnostics.is_empty() {
+            print!("no scheduling problems");
         }
+        for line in diagnostics {
+            print_styled(&mut self.stdout, &line, vec![], Some(Color::Red)).unwrap();
+            println!();
+            self.left_cursor();
+        }
+    }
 
-        None
+    fn draw_now_line(&mut self, now: TimeSinceMidnight) {
+        let s = format!("──── now {} ────", format_naive(now));
+        print_styled(&mut self.stdout, &s, vec![], Some(Color::Cyan)).unwrap();
+        println!();
+        self.left_cursor();
     }
 
-    pub fn run(&mut self) {
-        self.stdout
-            .execute(terminal::Clear(terminal::ClearType::All))
-            .unwrap();
+    fn draw_planned(&mut self) {
+        let (planned, available) = {
+            let day = self.selected_day.read();
+            (day.planned(), day.window().total)
+        };
 
-        self.draw();
-        let mut current_slot = self.current_slot();
-        if let Some(slot) = current_slot.clone() {
-            write_slot(&slot);
-        }
-        loop {
-            self.draw();
-            self.draw();
-            let event = match timed_input(5) {
-                Some(event) => {
-                    let new_slot = self.current_slot();
-                    if current_slot != new_slot {
-                        if let Some(slot) = &new_slot {
-                            on_new_slot(&slot);
-                        }
-                        current_slot = new_slot;
-                    }
+        let slack = if planned > available {
+            format!("{} over", format_dur(planned - available).trim())
+        } else {
+            format!("{} free", format_dur(available - planned).trim())
+        };
+        let s = format!(
+            "planned {} / {} ({slack})",
+            format_dur(planned).trim(),
+            format_dur(available).trim()
+        );
+        let color = (planned > available).then_some(Color::Red);
+
+        print_styled(&mut self.stdout, &s, vec![], color).unwrap();
+        println!();
+        self.left_cursor();
+    }
+
+    /// Shows the week of the selected day until a day is picked with enter or the view is left.
+    fn week_view(&mut self) {
+        let selected = self.selected_day.read().day;
+        let monday = selected.week(Weekday::Mon).first_day();
+        let mut index = selected.weekday().num_days_from_monday() as usize;
+
+        loop {
+            let days: Vec<LoadedDay> = (0..7)
+                .map(|offset| self.get_day(monday + Duration::days(offset)))
+                .collect();
+
+            self.draw_week(&days, index);
+
+            let Ok(event) = read() else {
+                continue;
+            };
+
+            match Action::from_event(event) {
+                Some(Action::Left) => index = index.saturating_sub(1),
+                Some(Action::Right) => index = (index + 1).min(6),
+                Some(Action::Edit) => {
+                    let day = days[index].read().day;
+                    self.load_or_create(day);
+                    return;
+                }
+                Some(Action::Quit) => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn draw_week(&mut self, days: &[LoadedDay], index: usize) {
+        const COLUMN_WIDTH: usize = 14;
+
+        self.clear_screen();
+        println!("week overview (enter to open day, q to go back)");
+        self.left_cursor();
+        println!();
+        self.left_cursor();
+
+        let rows: [fn(&Day) -> String; 3] = [
+            |day| day.day.format("%a %m-%d").to_string(),
+            |day| format!("{} slots", day.slots_config.len()),
+            |day| format_dur(day.planned()).trim().to_string(),
+        ];
+
+        for row in rows {
+            for (i, day) in days.iter().enumerate() {
+                let s = format!("{:COLUMN_WIDTH$}", row(&day.read()));
+                let attrs = if i == index {
+                    vec![Attribute::Reverse]
+                } else {
+                    vec![]
+                };
+                print_styled(&mut self.stdout, &s, attrs, None).unwrap();
+                print!(" ");
+            }
+            println!();
+            self.left_cursor();
+        }
+
+        self.flush();
+    }
+
+    fn draw_help(&mut self) {
+        self.clear_screen();
+        println!("keybindings (press any key to close)");
+        self.left_cursor();
+        println!();
+        self.left_cursor();
+
+        let mut actions: Vec<Action> = vec![];
+        let keymap = keymap();
+        for (_, _, action) in &keymap {
+            if !actions.contains(action) {
+                actions.push(*action);
+            }
+        }
+
+        for action in actions {
+            let keys: Vec<String> = keymap
+                .iter()
+                .filter(|(_, _, a)| *a == action)
+                .map(|(code, mods, _)| key_name(*code, *mods))
+                .collect();
+
+            println!("{:<16}{}", keys.join(", "), action.description());
+            self.left_cursor();
+        }
+
+        self.flush();
+    }
+
+    fn current_slot(&self) -> Option<SlotResult> {
+        let slots = self.days.get(&current_day())?.read().slots();
+        let idx = active_index(&slots, current_time())?;
+        Some(slots[idx].clone())
+    }
+
+    /// Puts the active slot in the terminal title, if enabled.
+    fn update_title(&mut self, slot: Option<&SlotResult>) {
+        if !self.title {
+            return;
+        }
+
+        let title = match slot {
+            Some(slot) => slot_title(slot, current_time()),
+            None => "dagplan".to_string(),
+        };
+        let _ = execute!(self.stdout, SetTitle(title));
+    }
+
+    /// Notifies once per slot when it's about to end, if enabled.
+    fn warn_if_ending(&mut self, slot: Option<&SlotResult>) {
+        if self.warn_before.is_zero() {
+            return;
+        }
+
+        let today = current_day();
+        self.warned_slots.retain(|(day, _)| *day == today);
+
+        let Some(slot) = slot else {
+            return;
+        };
+
+        if !self.notify || slot.configured.kind == SlotKind::Break || slot.configured.done {
+            return;
+        }
+
+        let end = slot.start + slot.length;
+        if current_time() < end - self.warn_before || !self.warned_slots.insert((today, slot.start))
+        {
+            return;
+        }
+
+        let s = format!(
+            "{} min left: {}",
+            (end - current_time()).num_minutes(),
+            &slot.configured.name
+        );
+        notify::send(&s, 6007);
+    }
+
+    /// Logs the slot that `ended` as it ran, and announces the `new` one.
+    fn change_slot(&mut self, ended: Option<&SlotResult>, new: Option<&SlotResult>) {
+        let now = now();
+        if let Some(ended) = ended.filter(|_| !self.readonly) {
+            let line = timelog::log_line(ended, self.slot_since, now);
+            if let Err(e) = timelog::append(&time_log_dir(), day_of(self.slot_since), &line) {
+                self.notice = Some(e);
+            }
+        }
+        self.slot_since = now;
+
+        if let Some(slot) = new {
+            on_new_slot(self.home_dir.as_deref(), slot, self.notify, notify::sink());
+        }
+    }
+
+    /// Sends a recap of today's plan once its planning window has closed.
+    fn summarize_if_over(&mut self) {
+        let today = current_day();
+        if !self.notify || !self.summary || self.summarized == Some(today) {
+            return;
+        }
+
+        let day = self.get_day(today);
+        let day = day.read();
+        let window = day.window();
+        if current_time() < window.start + window.total {
+            return;
+        }
+
+        self.summarized = Some(today);
+        let s = format!(
+            "Day over: {} slots, {}m planned",
+            day.slots_config.len(),
+            day.planned().num_minutes()
+        );
+        notify::send(&s, 6008);
+    }
+
+    pub fn run(&mut self) {
+        self.stdout
+            .execute(terminal::Clear(terminal::ClearType::All))
+            .unwrap();
+
+        self.draw();
+        let mut current_slot = self.current_slot();
+        match &current_slot {
+            Some(slot) => {
+                // It has been going since its start, whether or not the planner was open.
+                self.slot_since = now() - (current_time() - slot.start);
+                write_slot(self.home_dir.as_deref(), slot);
+            }
+            None => write_status(self.home_dir.as_deref(), None, current_time()),
+        }
+        self.update_title(current_slot.as_ref());
+        loop {
+            // Redraw before blocking on input so the screen reflects the last action and the
+            // current time. `draw` clears first and flushes last; nothing else may write to the
+            // terminal in between, or it ends up mixed into the frame.
+            self.draw();
+            let event = match timed_input(5) {
+                Some(event) => {
+                    let new_slot = self.current_slot();
+                    if current_slot != new_slot {
+                        self.change_slot(current_slot.as_ref(), new_slot.as_ref());
+                        current_slot = new_slot;
+                    }
+                    write_status(
+                        self.home_dir.as_deref(),
+                        current_slot.as_ref(),
+                        current_time(),
+                    );
+                    self.update_title(current_slot.as_ref());
+                    self.warn_if_ending(current_slot.as_ref());
+                    self.summarize_if_over();
 
                     event
                 }
                 None => {
                     let new_slot = self.current_slot();
                     if current_slot != new_slot {
-                        if let Some(slot) = &new_slot {
-                            on_new_slot(&slot);
-                        }
+                        self.change_slot(current_slot.as_ref(), new_slot.as_ref());
                         current_slot = new_slot;
                     }
+                    write_status(
+                        self.home_dir.as_deref(),
+                        current_slot.as_ref(),
+                        current_time(),
+                    );
+                    self.update_title(current_slot.as_ref());
+                    self.warn_if_ending(current_slot.as_ref());
+                    self.summarize_if_over();
 
                     continue;
                 }
             };
+            // The next draw lays the day out for the new size.
+            if let Event::Resize(..) = event {
+                continue;
+            }
             let Some(action) = Action::from_event(event) else {
                 continue;
             };
 
-            if self.handle_action(action).is_break() {
+            if self.apply(action).is_break() {
                 return;
             }
         }
     }
+
+    /// Handles `action` and writes the days it changed right away, so a crash, a signal or a
+    /// closed terminal can't lose them.
+    fn apply(&mut self, action: Action) -> ControlFlow<()> {
+        let flow = self.handle_action(action);
+        self.flush_all();
+        flow
+    }
 }
 
-fn write_slot(slot: &SlotResult) {
-    use std::io::Write;
-    let mut f = std::fs::File::create(dirs::home_dir().unwrap().join(".current_task")).unwrap();
-    f.write_all(slot.configured.name.as_bytes()).unwrap();
+/// Sets the requested length of every slot in `selection`.
+fn set_lengths(slots: &mut SlotDtos, selection: RangeInclusive<usize>, length: Duration) {
+    for idx in selection {
+        let Some(slot) = slots.get(idx) else {
+            break;
+        };
+        let mut slot = slot.clone();
+        slot.config.length = length;
+        slots.over_ride(idx, slot);
+    }
 }
 
-fn on_new_slot(slot: &SlotResult) {
-    write_slot(slot);
+/// The time from where slot `idx` is scheduled to start until the next fixed start after it, or
+/// until `window_end` if there is none.
+fn fill_length(slots: &[SlotResult], idx: usize, window_end: TimeSinceMidnight) -> Duration {
+    let end = slots[idx + 1..]
+        .iter()
+        .find(|slot| slot.configured.config.start.is_some())
+        .map_or(window_end, |slot| slot.start);
 
-    // Since mako doesn't support editing notifications in-place, nuke all notifs if last
-    // one was less than 10 sec ago. This will avoid multiple notifs at same time
-    // with the unfortunate side effect it will also remove other notifs from other processes.
-    if update_timestamp() < std::time::Duration::from_secs(10) {
-        let _ = std::process::Command::new("makoctl")
-            .arg("dismiss")
-            .output();
-    }
+    (end - slots[idx].start).max(Duration::zero())
+}
 
-    let s = format!("new task: {}", &slot.configured.name);
-    let _ = Notification::new().summary(&s).id(6006).show();
+/// The index of the slot that `now` falls within.
+fn active_index(slots: &[SlotResult], now: TimeSinceMidnight) -> Option<usize> {
+    slots
+        .iter()
+        .position(|slot| slot.start <= now && now < slot.start + slot.length)
 }
 
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::OnceLock;
+/// Writes the name of the slot to `.current_task` in `home_dir`, for status bars and the like.
+/// Does nothing without a home directory.
+fn write_slot(home_dir: Option<&Path>, slot: &SlotResult) {
+    let Some(home_dir) = home_dir else {
+        return;
+    };
+
+    let _ = std::fs::write(home_dir.join(".current_task"), task_name(slot));
+    write_status(Some(home_dir), Some(slot), current_time());
+}
+
+/// The name shown for the active slot outside the planner. Empty for breaks.
+fn task_name(slot: &SlotResult) -> &str {
+    match slot.configured.kind {
+        SlotKind::Break => "",
+        SlotKind::Activity | SlotKind::Filler => &slot.configured.name,
+    }
+}
 
-static LAST_TIMESTAMP: OnceLock<AtomicU64> = OnceLock::new();
+/// The terminal title for the active slot, like `code review — 25m left`. Minutes are
+/// rounded up, so it says `1m left` until the slot is over.
+fn slot_title(slot: &SlotResult, now: TimeSinceMidnight) -> String {
+    let name = match task_name(slot) {
+        "" => "break",
+        name => name,
+    };
+    let left = (slot.start + slot.length - now).num_seconds().max(0);
+    format!("{name} — {}m left", (left + 59) / 60)
+}
+
+/// The active slot as written to `.current_task.json`.
+#[derive(Serialize, PartialEq, Debug)]
+struct SlotStatus {
+    name: String,
+    start: String,
+    end: String,
+    remaining_secs: i64,
+}
 
-fn timestamp_store() -> &'static AtomicU64 {
-    LAST_TIMESTAMP.get_or_init(|| AtomicU64::new(0))
+impl SlotStatus {
+    fn new(slot: &SlotResult, now: TimeSinceMidnight) -> Self {
+        let end = slot.start + slot.length;
+        Self {
+            name: task_name(slot).to_string(),
+            start: format_naive(slot.start),
+            end: format_naive(end),
+            remaining_secs: (end - now).num_seconds().max(0),
+        }
+    }
 }
 
-fn current_unix_time() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
+/// Writes the active slot to `.current_task.json` in `home_dir`, or `{}` when there is none.
+fn write_status(home_dir: Option<&Path>, slot: Option<&SlotResult>, now: TimeSinceMidnight) {
+    let Some(home_dir) = home_dir else {
+        return;
+    };
+
+    let json = match slot {
+        Some(slot) => serde_json::to_string(&SlotStatus::new(slot, now)).unwrap(),
+        None => "{}".to_string(),
+    };
+    let _ = std::fs::write(home_dir.join(".current_task.json"), json);
 }
 
-fn update_timestamp() -> std::time::Duration {
-    let now = current_unix_time();
-    let prev = timestamp_store().swap(now, Ordering::SeqCst);
-    std::time::Duration::from_secs(now - prev)
+/// Writes the new active slot to `.current_task`, and unless `notify` is off, tells `sink` about it.
+fn on_new_slot(
+    home_dir: Option<&Path>,
+    slot: &SlotResult,
+    notify: bool,
+    sink: &dyn NotificationSink,
+) {
+    write_slot(home_dir, slot);
+
+    if !notify || slot.configured.kind == SlotKind::Break || slot.configured.done {
+        return;
+    }
+
+    let s = format!("new task: {}", &slot.configured.name);
+    sink.notify(&s, 6006);
 }
 
+use std::sync::OnceLock;
+
 pub fn timed_input(timeout_secs: u64) -> Option<Event> {
     if event::poll(std::time::Duration::from_secs(timeout_secs)).ok()? {
         event::read().ok()
@@ -622,23 +2592,71 @@ pub fn timed_input(timeout_secs: u64) -> Option<Event> {
     }
 }
 
+/// Drawn in front of a slot to tell events, with a fixed start and length, from tasks, whose
+/// length is elastic. Fixed length slots without a start are neither.
+fn schedule_glyph(config: &TimeSlotConfig, ascii: bool) -> char {
+    match (config.start.is_some(), config.fixed_length, ascii) {
+        (true, true, false) => '◆',
+        (true, true, true) => '#',
+        (_, false, false) => '○',
+        (_, false, true) => 'o',
+        (false, true, _) => ' ',
+    }
+}
+
+/// What's drawn next to the active slot: a clock face showing `time`, or `>` in ASCII mode.
+fn active_marker(time: TimeSinceMidnight, ascii: bool) -> char {
+    if ascii {
+        '>'
+    } else {
+        clock_emoji(time)
+    }
+}
+
+/// The clock face closest to `time`, to the half hour.
 fn clock_emoji(time: TimeSinceMidnight) -> char {
     let (hour, minute) = hour_and_minute(time);
-    let rounded_hour = match minute {
-        0..=14 => hour,
-        15..=44 => hour, // or return half-hour later if you want 🕦
-        _ => hour + 1,
-    } % 12;
-
-    let base = 0x1F550;
-    let codepoint = base
-        + if rounded_hour == 0 {
-            11
-        } else {
-            rounded_hour - 1
-        };
+    let (hour, half) = match minute {
+        0..=14 => (hour, false),
+        15..=44 => (hour, true),
+        _ => (hour + 1, false),
+    };
+
+    // The faces for 1 to 12 o'clock come first, then those for half past 1 to half past 12.
+    let face = (hour + 11) % 12;
+    let base = if half { 0x1F55C } else { 0x1F550 };
 
-    std::char::from_u32(codepoint).unwrap_or('🕛') // fallback just in case
+    std::char::from_u32(base + face).unwrap_or('🕛') // fallback just in case
+}
+
+fn warning_color(warning: &Result<(), ScheduleError>) -> Option<Color> {
+    match warning {
+        Ok(()) => None,
+        Err(ScheduleError::InsufficientFixedTime | ScheduleError::InvalidLength) => {
+            Some(Color::Red)
+        }
+        Err(ScheduleError::NoElasticSlots) => Some(Color::Yellow),
+    }
+}
+
+const PROGRESS_BAR_WIDTH: usize = 8;
+
+/// A bar like `[####----] 52%` showing how far into the slot `now` is.
+fn progress_bar(slot: &SlotResult, now: TimeSinceMidnight) -> String {
+    let ratio = if slot.length.is_zero() {
+        1.0
+    } else {
+        let elapsed = (now - slot.start).num_seconds() as f64;
+        (elapsed / slot.length.num_seconds() as f64).clamp(0.0, 1.0)
+    };
+
+    let filled = (ratio * PROGRESS_BAR_WIDTH as f64).round() as usize;
+    format!(
+        "[{}{}] {:.0}%",
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH - filled),
+        ratio * 100.0
+    )
 }
 
 fn hour_and_minute(time: TimeSinceMidnight) -> (u32, u32) {
@@ -654,177 +2672,523 @@ fn format_dur(dur: Duration) -> String {
     format!("{:>5}m", mins)
 }
 
-fn format_naive(time: TimeSinceMidnight) -> String {
-    let (hours, minutes) = hour_and_minute(time);
-    format!("{:02}:{:02}", hours, minutes)
+/// Inserts a copy of slot `idx` right after it. The copy has no fixed start, so the starts stay in order.
+fn duplicate(slots: &mut SlotDtos, idx: usize) {
+    let Some(slot) = slots.get(idx) else {
+        return;
+    };
+
+    let mut copy = slot.clone();
+    copy.config.start = None;
+    slots.insert(idx + 1, copy);
 }
 
-#[derive(Serialize, Deserialize, Default)]
-pub struct SlotDtos(Vec<SlotDto>);
+fn write_slots_json(path: &Path, slots: &SlotDtos) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(slots)?;
+    std::fs::write(path, json)
+}
 
-impl Deref for SlotDtos {
-    type Target = Vec<SlotDto>;
+/// Reads slots written by [`write_slots_json`], rejecting them if their start times are out of order.
+fn read_slots_json(path: &Path) -> io::Result<SlotDtos> {
+    let json = std::fs::read_to_string(path)?;
+    let slots: SlotDtos = serde_json::from_str(&json)?;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    if SlotDtos::validate(&slots).is_err() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "slot start times are not in order",
+        ));
     }
-}
 
-impl SlotDtos {
-    pub fn insert(&mut self, index: usize, slot: SlotDto) {
-        let mut inner = self.0.clone();
-        if index >= inner.len() {
-            inner.push(slot);
-        } else {
-            inner.insert(index, slot);
-        }
+    Ok(slots)
+}
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
+/// The time scheduled for each category, most first. Slots without a category aren't counted.
+fn category_totals(slots: &[SlotResult]) -> Vec<(String, Duration)> {
+    let mut totals: HashMap<&str, Duration> = HashMap::new();
+    for slot in slots {
+        if let Some(category) = &slot.configured.category {
+            *totals.entry(category).or_insert_with(Duration::zero) += slot.length;
         }
     }
 
-    pub fn remove(&mut self, idx: usize) {
-        if idx >= self.0.len() {
-            return;
-        }
+    let mut totals: Vec<(String, Duration)> = totals
+        .into_iter()
+        .map(|(category, total)| (category.to_string(), total))
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
 
-        let mut inner = self.0.clone();
-        inner.remove(idx);
+/// How much the scheduled length strays from the requested one, e.g. `(−15m)`, when it's more
+/// than a minute.
+fn length_delta(slot: &SlotResult) -> Option<String> {
+    let diff = slot.length - slot.configured.config.length;
+    if diff.num_seconds().abs() <= 60 {
+        return None;
+    }
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
-        }
+    let sign = if diff < Duration::zero() { '−' } else { '+' };
+    Some(format!("({sign}{}m)", diff.num_seconds().abs() / 60))
+}
+
+/// The terminal's columns and rows, or `usize::MAX` for either that can't be told.
+fn terminal_size() -> (usize, usize) {
+    let known = |n: u16| if n > 0 { n as usize } else { usize::MAX };
+    match terminal::size() {
+        Ok((columns, rows)) => (known(columns), known(rows)),
+        Err(_) => (usize::MAX, usize::MAX),
     }
+}
 
-    pub fn unset_start(&mut self, idx: usize) {
-        if idx >= self.0.len() {
-            return;
-        }
+/// How wide a slot row is without its name, including the mark in front and the progress of
+/// the active slot after it.
+fn row_width_without_name(hidden: &[Field]) -> usize {
+    let mark = 2;
+    let active = 3 + PROGRESS_BAR_WIDTH + 7;
+    let columns: usize = Field::ALL
+        .iter()
+        .filter(|field| !hidden.contains(field))
+        .map(|field| {
+            let width = match field {
+                Field::Name => 0,
+                Field::Start | Field::End | Field::Weight => 5,
+                Field::Requested => 6,
+                // The difference from the requested length is shown along with it.
+                Field::Length if hidden.contains(&Field::Requested) => 6,
+                Field::Length => 6 + 9,
+            };
+            width + 3
+        })
+        .sum();
+    mark + columns + active
+}
 
-        let mut inner = self.0.clone();
-        inner[idx].config.start = None;
+/// The name column's width: `wanted`, narrowed so the rest of the row still fits in `width`
+/// terminal columns, but never below 4.
+fn fit_name_width(wanted: usize, rest: usize, width: usize) -> usize {
+    wanted.min(width.saturating_sub(rest)).max(4)
+}
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
-        }
+/// The longest a name gets in compact mode.
+const COMPACT_NAME_WIDTH: usize = 20;
+
+/// The columns left out of the list. In compact mode, that's the requested length when every
+/// slot got what it asked for.
+fn hidden_fields(slots: &[SlotResult], compact: bool) -> Vec<Field> {
+    if compact && slots.iter().all(|slot| length_delta(slot).is_none()) {
+        vec![Field::Requested]
+    } else {
+        vec![]
     }
+}
 
-    pub fn set_start(&mut self, idx: usize, start: TimeSinceMidnight) {
-        if idx >= self.0.len() {
-            return;
-        }
+/// `name` cut down to `width` characters, ending in `…` if anything was cut.
+fn truncate(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        return name.to_string();
+    }
 
-        let mut inner = self.0.clone();
-        inner[idx].config.start = Some(start);
+    let mut out: String = name.chars().take(width.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
-        }
+/// How many lines a slot takes up in the list.
+fn slot_rows(slot: &SlotResult) -> usize {
+    1 + usize::from(slot.configured.note.is_some())
+}
+
+/// The first slot to show so the cursor's rows are on screen, moving `offset` as little as
+/// possible. A block separator takes a row as well.
+fn scroll_into_view(slots: &[SlotResult], offset: usize, cursor: usize, rows: usize) -> usize {
+    let mut offset = offset.min(cursor);
+    let rows_to_cursor = |offset: usize| {
+        let separators = (offset + 1..=cursor)
+            .filter(|&i| slots[i - 1].block != slots[i].block)
+            .count();
+        slots[offset..=cursor].iter().map(slot_rows).sum::<usize>() + separators
+    };
+
+    while offset < cursor && rows_to_cursor(offset) > rows {
+        offset += 1;
     }
+    offset
+}
 
-    pub fn swap(&mut self, i: usize, j: usize) {
-        let len = self.0.len();
+/// How far the last slot ends past the end of the window, if it does.
+fn overrun(slots: &[SlotResult], window: PlanWindow) -> Option<Duration> {
+    let last = slots.last()?;
+    let over = last.start + last.length - (window.start + window.total);
+    (over > Duration::zero()).then_some(over)
+}
 
-        if i >= len || j >= len {
-            return;
-        }
+/// The day `input` points to relative to `from`, either as a signed number of days or `today`.
+fn relative_day(from: NaiveDate, input: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    if input == "today" {
+        return Some(current_day());
+    }
 
-        let mut inner = self.0.clone();
-        inner.swap(i, j);
+    let days: i64 = input.parse().ok()?;
+    from.checked_add_signed(Duration::try_days(days)?)
+}
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
-        }
+/// Parses a length given as minutes (`90`), hours and minutes (`1:30`) or a humantime string (`1h30m`).
+fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+
+    if let Ok(mins) = s.parse::<u32>() {
+        return Some(Duration::minutes(mins as i64));
     }
 
-    pub fn over_ride(&mut self, index: usize, slot: SlotDto) {
-        let mut inner = self.0.clone();
-        if index >= inner.len() {
-            return;
-        } else {
-            inner[index] = slot;
+    if let Some((hours, mins)) = s.split_once(':') {
+        let hours: u32 = hours.parse().ok()?;
+        let mins: u32 = mins.parse().ok()?;
+        if mins >= 60 {
+            return None;
         }
+        return Some(Duration::minutes(hours as i64 * 60 + mins as i64));
+    }
 
-        if Self::validate(&inner).is_ok() {
-            self.0 = inner;
-        }
+    let dur = humantime::parse_duration(s).ok()?;
+    Duration::from_std(dur).ok()
+}
+
+/// A start given as `HH:MM`, or as `+30` for that long after `now`, and whether it's soft, which
+/// is written with a `~` in front.
+fn parse_start(s: &str, now: TimeSinceMidnight) -> Option<(TimeSinceMidnight, bool)> {
+    let s = s.trim();
+    let (soft, s) = match s.strip_prefix('~') {
+        Some(rest) => (true, rest.trim()),
+        None => (false, s),
+    };
+
+    let time = match s.strip_prefix('+') {
+        Some(offset) => now + parse_duration(offset)?,
+        None => naive_to_timesincemidnight(NaiveTime::parse_from_str(s, "%H:%M").ok()?),
+    };
+    Some((time, soft))
+}
+
+/// Like [`parse_duration`], with an optional `+` or `-` in front.
+fn parse_offset(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    match s.strip_prefix('-') {
+        Some(rest) => parse_duration(rest).map(|dur| -dur),
+        None => parse_duration(s.strip_prefix('+').unwrap_or(s)),
     }
+}
 
-    pub fn make_valid(&mut self) {
-        let mut last_start: Option<TimeSinceMidnight> = None;
+/// `time` rounded to the nearest multiple of `step`, halves rounding up. A zero step leaves it as is.
+///
+/// Since a slot ends where the next one starts and both get rounded the same way, rounded times
+/// never make neighbours overlap.
+fn round_time(time: TimeSinceMidnight, step: Duration) -> TimeSinceMidnight {
+    let step = step.num_seconds();
+    if step <= 0 {
+        return time;
+    }
 
-        for slot in &mut self.0 {
-            let valid_time = if let Some(t) = &slot.config.start {
-                if let Some(prev_t) = &last_start {
-                    if t < prev_t {
-                        false
-                    } else {
-                        last_start = Some(*t);
-                        true
-                    }
-                } else {
-                    true
-                }
-            } else {
-                false
-            };
+    let secs = (time.num_seconds() + step / 2) / step * step;
+    TimeSinceMidnight::seconds(secs)
+}
 
-            if !valid_time {
-                slot.config.start = None;
-            }
+fn format_naive(time: TimeSinceMidnight) -> String {
+    let (hours, minutes) = hour_and_minute(time);
+    format!("{:02}:{:02}", hours % 24, minutes)
+}
+
+/// The part of the day that slots are planned within.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct PlanWindow {
+    start: TimeSinceMidnight,
+    total: Duration,
+}
+
+impl Default for PlanWindow {
+    fn default() -> Self {
+        Self {
+            start: t(7, 0),
+            total: dur(16 * 60),
         }
     }
+}
 
-    fn validate(slots: &Vec<SlotDto>) -> Result<(), ()> {
-        let mut last_start: Option<TimeSinceMidnight> = None;
+/// The plan window from config.toml.
+static DEFAULT_WINDOW: OnceLock<PlanWindow> = OnceLock::new();
 
-        for slot in slots {
-            if let Some(t) = &slot.config.start {
-                if let Some(prev_t) = &last_start {
-                    if t < prev_t {
-                        return Err(());
-                    } else {
-                        last_start = Some(*t);
-                    }
-                }
-            }
-        }
+fn default_window() -> PlanWindow {
+    DEFAULT_WINDOW.get().copied().unwrap_or_default()
+}
 
-        Ok(())
-    }
+/// The new slot settings from config.toml.
+static SLOT_DEFAULTS: OnceLock<SlotDefaults> = OnceLock::new();
+
+fn slot_defaults() -> &'static SlotDefaults {
+    SLOT_DEFAULTS.get_or_init(SlotDefaults::default)
+}
+
+/// The category colors from config.toml.
+static CATEGORY_COLORS: OnceLock<HashMap<String, Color>> = OnceLock::new();
+
+fn category_color(category: Option<&str>) -> Option<Color> {
+    CATEGORY_COLORS.get()?.get(category?).copied()
 }
 
+/// The time log directory from config.toml.
+static TIME_LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+fn time_log_dir() -> PathBuf {
+    TIME_LOG_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| profile_root(Day::crate_name()).join("log"))
+}
+
+/// The version of the day file format written by this build.
+const SCHEMA_VERSION: u32 = 1;
+
 #[derive(Serialize, Deserialize)]
 struct Day {
+    /// Files from before versions were recorded are version 0.
+    #[serde(default)]
+    schema_version: u32,
     day: NaiveDate,
     slots_config: SlotDtos,
+    /// Shown next to the date, like "Conference day".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    /// Things that hold for the whole day, like "on-call", shown under the date. They take no time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    banners: Vec<String>,
     #[serde(skip)]
-    slot_result: SingletonCache<Vec<SlotDto>, Vec<SlotResult>>,
+    slot_result: SingletonCache<SlotsKey, Vec<SlotResult>>,
 }
 
 impl DefaultWithId for Day {
+    /// Days from today on start out with the recurring slots.
     fn default_with_id(id: Self::Key) -> Self {
+        let slots_config = if id >= current_day() {
+            recur::slots_for(id, &Recurring::load_all())
+        } else {
+            Default::default()
+        };
+
         Self {
+            schema_version: SCHEMA_VERSION,
             day: id,
-            slots_config: Default::default(),
+            slots_config,
+            label: None,
+            banners: vec![],
             slot_result: Default::default(),
         }
     }
 }
 
 impl Day {
-    fn insert(&mut self, cursor: Cursor) {
-        let index = cursor.index.clamp(0, self.slots_config.len());
-        let new_slot = SlotDto::default();
-        self.slots_config.insert(index, new_slot);
+    /// Inserts a new slot made from `defaults` at `index`, or at the end if that's past it. Returns
+    /// where it went.
+    fn insert(&mut self, index: usize, defaults: &SlotDefaults) -> usize {
+        let index = index.min(self.slots_config.len());
+        self.slots_config.insert(index, defaults.slot());
+        index
+    }
+
+    /// Parses a saved day, migrating it to [`SCHEMA_VERSION`].
+    fn from_json(s: &str) -> serde_json::Result<Self> {
+        let mut day: Self = serde_json::from_str(s)?;
+        day.migrate();
+        Ok(day)
+    }
+
+    fn migrate(&mut self) {
+        // 0 to 1 only started recording the version.
+        if self.schema_version == 0 {
+            self.schema_version = 1;
+        }
+    }
+
+    /// Sets the label shown next to the date. A blank one removes it.
+    fn set_label(&mut self, label: &str) {
+        let label = label.trim();
+        self.label = (!label.is_empty()).then(|| label.to_string());
+    }
+
+    /// Adds an all-day note, unless it's blank.
+    fn add_banner(&mut self, banner: &str) {
+        let banner = banner.trim();
+        if !banner.is_empty() {
+            self.banners.push(banner.to_string());
+        }
+    }
+
+    fn remove_banner(&mut self, idx: usize) {
+        if idx < self.banners.len() {
+            self.banners.remove(idx);
+        }
+    }
+
+    fn window(&self) -> PlanWindow {
+        default_window()
     }
 
     fn slots(&self) -> Arc<Vec<SlotResult>> {
-        let f: Box<dyn Fn(&Vec<SlotDto>) -> Vec<SlotResult>> =
-            Box::new(|slots: &Vec<SlotDto>| calculate_slots(t(7, 0), dur(16 * 60), slots.clone()));
+        let f: SlotCalc<SlotsKey> = Box::new(|(slots, window): &SlotsKey| {
+            calculate_slots(window.start, window.total, slots.clone())
+        });
+
+        let key = (self.slots_config.to_vec(), self.window());
+        self.slot_result.get(&key, f)
+    }
+
+    /// Problems with how the day is planned that the schedule silently papers over.
+    fn diagnostics(&self) -> Vec<String> {
+        diagnostics(&self.slots_config, self.window())
+    }
+
+    /// The sum of the lengths the slots asked for.
+    fn planned(&self) -> Duration {
+        self.slots_config
+            .iter()
+            .map(|slot| slot.config.length)
+            .sum()
+    }
+}
+
+/// The blocks that ask for more time than they have, described for the diagnostics pane.
+fn diagnostics(slots: &[SlotDto], window: PlanWindow) -> Vec<String> {
+    overcommitted_blocks(window.start, window.total, slots.to_vec())
+        .into_iter()
+        .map(|(start, over)| {
+            format!(
+                "block at {} over-committed by {}m",
+                format_naive(start),
+                over.num_minutes()
+            )
+        })
+        .collect()
+}
+
+/// Applies `f` to the day, undoing it if it moves a fixed start outside the plan window. Days
+/// that already had one can still be edited, so they can be fixed.
+fn edit_within_window(day: &mut Day, f: impl FnOnce(&mut Day)) -> Result<(), usize> {
+    let window = day.window();
+    let check =
+        |day: &Day| SlotDtos::validate_within(&day.slots_config, window.start, window.total);
+
+    let before = day.slots_config.clone();
+    let was_valid = check(day).is_ok();
+    f(day);
+
+    match check(day) {
+        Err(idx) if was_valid => {
+            day.slots_config = before;
+            Err(idx)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Applies `f` to the day like [`edit_within_window`], marking it for saving only if its slots
+/// changed. Returns the slots from before if they did.
+fn edit_slots(day: &LoadedDay, f: impl FnOnce(&mut Day)) -> (Option<SlotDtos>, Result<(), usize>) {
+    let mut guard = day.write();
+    let before = guard.slots_config.clone();
+    let result = edit_within_window(&mut guard, f);
+    if guard.slots_config == before {
+        return (None, result);
+    }
+
+    drop(guard);
+    day.mark_dirty();
+    (Some(before), result)
+}
+
+/// Locks the day for writing, marking it to be saved on the next flush.
+fn write_day(day: &LoadedDay) -> RwLockWriteGuard<'_, Day> {
+    day.mark_dirty();
+    day.write()
+}
+
+/// A day kept in memory while the planner runs. Edits only mark it dirty; it's written to disk
+/// when it's flushed after each action, which is skipped if nothing changed since the last time.
+#[derive(Clone)]
+struct LoadedDay {
+    day: Arc<RwLock<Day>>,
+    dirty: Arc<AtomicBool>,
+}
 
-        self.slot_result.get(&self.slots_config, f)
+impl LoadedDay {
+    fn new(day: Day, dirty: bool) -> Self {
+        Self {
+            day: Arc::new(RwLock::new(day)),
+            dirty: Arc::new(AtomicBool::new(dirty)),
+        }
+    }
+
+    /// The saved day, or a new one that's only written once it's edited. Readonly instances
+    /// never edit, so they never create day files.
+    fn load_or_create(id: NaiveDate) -> Self {
+        let day = Day::load(id).unwrap_or_else(|| Day::default_with_id(id));
+        Self::new(day, false)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Day> {
+        self.day.try_read().unwrap()
+    }
+
+    /// Locks the day for writing without marking it dirty. Use [`write_day`] unless the caller
+    /// checks for changes itself.
+    fn write(&self) -> RwLockWriteGuard<'_, Day> {
+        self.day.try_write().unwrap()
+    }
+
+    fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    /// Writes the day if it changed, backing up the file on disk first since it gets overwritten.
+    /// The day is written even if the backup fails, which is then returned as the error.
+    fn flush(&self) -> Result<(), String> {
+        if !self.is_dirty() {
+            return Ok(());
+        }
+
+        let day = self.read();
+        let backed_up = backup::backup(&day.item_path());
+        day.save().unwrap();
+        self.dirty.store(false, Ordering::Relaxed);
+        backed_up
+    }
+}
+
+/// The profile given on the command line, if any.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Where days are kept when the system has no data directory: `--home-dir`, or `$XDG_STATE_HOME`.
+static DATA_FALLBACK: OnceLock<PathBuf> = OnceLock::new();
+
+/// The system's data directory, or `fallback` when it has none.
+fn data_dir(system: Option<PathBuf>, fallback: Option<&PathBuf>) -> Option<PathBuf> {
+    system.or_else(|| fallback.cloned())
+}
+
+/// The storage root for `crate_name`, nested one level deeper when a profile is in use. Exits if
+/// there's nowhere to keep it.
+fn profile_root(crate_name: String) -> PathBuf {
+    let Some(data) = data_dir(dirs::data_local_dir(), DATA_FALLBACK.get()) else {
+        eprintln!("no data directory found, use --home-dir to pick where days are kept");
+        std::process::exit(1);
+    };
+    let root = data.join(crate_name);
+    match PROFILE.get() {
+        Some(profile) => root.join(profile),
+        None => root,
     }
 }
 
@@ -834,10 +3198,81 @@ impl FsTrait for Day {
     fn item_id(&self) -> Self::Key {
         self.day
     }
+
+    fn root() -> PathBuf {
+        profile_root(Self::crate_name())
+    }
+
+    /// Same as the default, but brings days saved by older versions up to date and sets aside
+    /// files that can't be read.
+    fn load(id: Self::Key) -> Option<Self> {
+        let path = Self::items_path().join(id.to_string());
+        if !path.exists() {
+            return None;
+        }
+
+        read_day_file(&path)
+    }
+}
+
+/// Notices about unreadable day files found since the planner last reported them.
+static CORRUPT_DAYS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Parses the day saved at `path`. A file that can't be read or parsed, like one cut short by a
+/// crash, is moved to `<path>.corrupt` and treated as missing, so a fresh day replaces it.
+fn read_day_file(path: &Path) -> Option<Day> {
+    let e = match std::fs::read_to_string(path) {
+        Ok(s) => match Day::from_json(&s) {
+            Ok(day) => return Some(day),
+            Err(e) => e.to_string(),
+        },
+        Err(e) => e.to_string(),
+    };
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupt");
+    let backup = PathBuf::from(backup);
+    let notice = match std::fs::rename(path, &backup) {
+        Ok(()) => format!(
+            "unreadable day file moved to {}, started a new day",
+            backup.display()
+        ),
+        Err(rename) => format!(
+            "{}: {e}, and moving it to {} failed: {rename}",
+            path.display(),
+            backup.display()
+        ),
+    };
+    CORRUPT_DAYS.lock().unwrap().push(notice);
+    None
+}
+
+/// A warning about the day files found unreadable since this was last called, if any were.
+fn corrupt_days_notice() -> Option<String> {
+    mem::take(&mut *CORRUPT_DAYS.lock().unwrap()).pop()
+}
+
+/// Where the cursor was last left on a given day.
+#[derive(Serialize, Deserialize)]
+struct DayCursor {
+    day: NaiveDate,
+    cursor: Cursor,
+}
+
+impl FsTrait for DayCursor {
+    type Key = NaiveDate;
+
+    fn item_id(&self) -> Self::Key {
+        self.day
+    }
+
+    fn root() -> PathBuf {
+        profile_root(Self::crate_name())
+    }
 }
 
 /// An activity, not tied to a specific instance, can be shared between days and slots
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct Act {
     name: String,
     id: ActId,
@@ -849,8 +3284,13 @@ impl FsTrait for Act {
     fn item_id(&self) -> Self::Key {
         self.id
     }
+
+    fn root() -> PathBuf {
+        profile_root(Self::crate_name())
+    }
 }
 
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize, Default, Debug)]
 struct DayDto {
     day: NaiveDate,
@@ -863,4 +3303,708 @@ impl FsTrait for DayDto {
     fn item_id(&self) -> Self::Key {
         self.day
     }
+
+    fn root() -> PathBuf {
+        profile_root(Self::crate_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn exported_slots_import_unchanged() {
+        let mut slots = SlotDtos::default();
+        slots.insert(0, SlotDto::default());
+        let mut anchored = SlotDto::default();
+        anchored.config.start = Some(t(12, 0));
+        anchored.config.fixed_length = true;
+        slots.insert(1, anchored);
+
+        let path = std::env::temp_dir().join(format!("dagplan-test-{}.json", Uuid::new_v4()));
+        write_slots_json(&path, &slots).unwrap();
+        let imported = read_slots_json(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(imported.unwrap() == slots);
+    }
+
+    #[test]
+    fn saved_cursor_keeps_index_and_field() {
+        let state = DayCursor {
+            day: "2025-03-28".parse().unwrap(),
+            cursor: Cursor {
+                index: 3,
+                field: Field::Weight,
+            },
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: DayCursor = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.cursor.index, 3);
+        assert!(restored.cursor.field == Field::Weight);
+    }
+
+    #[test]
+    fn hours_after_midnight_belong_to_the_previous_day() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+
+        assert_eq!(day_of(at("2025-03-29 02:59")), date("2025-03-28"));
+        assert_eq!(day_of(at("2025-03-29 03:00")), date("2025-03-29"));
+        assert_eq!(
+            naive_to_timesincemidnight(NaiveTime::from_hms_opt(1, 0, 0).unwrap()),
+            t(25, 0)
+        );
+    }
+
+    #[test]
+    fn a_new_window_recomputes_the_slots() {
+        let cache: SingletonCache<SlotsKey, Vec<SlotResult>> = Default::default();
+        let calc = || -> SlotCalc<SlotsKey> {
+            Box::new(|(slots, window): &SlotsKey| {
+                calculate_slots(window.start, window.total, slots.clone())
+            })
+        };
+        let window = |start| PlanWindow {
+            start,
+            total: dur(60),
+        };
+        let slots = vec![SlotDto::default()];
+
+        let first = cache.get(&(slots.clone(), window(t(7, 0))), calc());
+        let second = cache.get(&(slots, window(t(9, 0))), calc());
+
+        assert_eq!(first[0].start, t(7, 0));
+        assert_eq!(second[0].start, t(9, 0));
+    }
+
+    #[test]
+    fn duplicate_goes_right_below_the_original() {
+        let named = |name: &str| SlotDto {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let mut slots = SlotDtos::default();
+        slots.insert(0, named("a"));
+        slots.insert(1, named("b"));
+        let mut anchored = slots[0].clone();
+        anchored.config.start = Some(t(9, 0));
+        slots.over_ride(0, anchored);
+
+        duplicate(&mut slots, 0);
+
+        let names: Vec<&str> = slots.iter().map(|slot| slot.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "a", "b"]);
+        assert_eq!(slots[1].config.start, None);
+    }
+
+    #[test]
+    fn acts_load_as_they_were_saved() {
+        let act = Act {
+            name: "deep work".to_string(),
+            id: Uuid::new_v4(),
+        };
+        act.save().unwrap();
+        let loaded = Act::load(act.id);
+        Act::delete(act.id);
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.id, act.id);
+        assert_eq!(loaded.name, "deep work");
+    }
+
+    #[test]
+    fn lengths_parse_as_minutes_clock_or_humantime() {
+        assert_eq!(parse_duration("90"), Some(dur(90)));
+        assert_eq!(parse_duration("1:30"), Some(dur(90)));
+        assert_eq!(parse_duration("1h30m"), Some(dur(90)));
+        assert_eq!(parse_duration(" 45 "), Some(dur(45)));
+        assert_eq!(parse_duration("1:75"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    fn scheduled(requested: Duration, length: Duration) -> SlotResult {
+        let mut configured = SlotDto::default();
+        configured.config.length = requested;
+        SlotResult {
+            start: t(9, 0),
+            length,
+            warning: Ok(()),
+            configured,
+            block: 0,
+        }
+    }
+
+    #[test]
+    fn length_delta_shows_shrinking_and_growing() {
+        assert_eq!(
+            length_delta(&scheduled(dur(60), dur(45))).as_deref(),
+            Some("(−15m)")
+        );
+        assert_eq!(
+            length_delta(&scheduled(dur(60), dur(75))).as_deref(),
+            Some("(+15m)")
+        );
+        assert_eq!(
+            length_delta(&scheduled(dur(60), dur(60) + Duration::seconds(30))),
+            None
+        );
+    }
+
+    #[test]
+    fn diagnostics_name_the_overcommitted_block() {
+        let mut day = Day::default_with_id(date("2025-03-28"));
+        let mut fixed = SlotDto::default();
+        fixed.config.start = Some(t(9, 0));
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(20 * 60);
+        day.slots_config.insert(0, fixed);
+
+        assert_eq!(
+            day.diagnostics(),
+            vec!["block at 09:00 over-committed by 240m"]
+        );
+    }
+
+    #[test]
+    fn relative_day_moves_by_signed_days() {
+        let from = date("2025-03-28");
+
+        assert_eq!(relative_day(from, "+7"), Some(date("2025-04-04")));
+        assert_eq!(relative_day(from, "-3"), Some(date("2025-03-25")));
+        assert_eq!(relative_day(from, "today"), Some(current_day()));
+        assert_eq!(relative_day(from, "soon"), None);
+    }
+
+    #[test]
+    fn insert_positions() {
+        let mut day = Day::default_with_id(date("2025-03-28"));
+        day.insert(0, &SlotDefaults::default());
+        day.slots_config.over_ride(
+            0,
+            SlotDto {
+                name: "a".into(),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(day.insert(0, &SlotDefaults::default()), 0);
+        assert_eq!(day.insert(9, &SlotDefaults::default()), 2);
+        let names: Vec<&str> = day
+            .slots_config
+            .iter()
+            .map(|slot| slot.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["...", "a", "..."]);
+    }
+
+    #[test]
+    fn ascii_marks_the_active_slot_with_a_chevron() {
+        assert_eq!(active_marker(t(9, 30), true), '>');
+        assert_eq!(active_marker(t(9, 30), false), clock_emoji(t(9, 30)));
+    }
+
+    #[test]
+    fn clock_faces_round_to_the_half_hour() {
+        let face = |h, m| clock_emoji(t(h, m)) as u32;
+
+        assert_eq!(face(9, 0), 0x1F558);
+        assert_eq!(face(9, 14), 0x1F558);
+        assert_eq!(face(9, 30), 0x1F564);
+        assert_eq!(face(9, 45), 0x1F559);
+        assert_eq!(face(12, 0), 0x1F55B);
+        assert_eq!(face(12, 30), 0x1F567);
+        assert_eq!(face(25, 0), 0x1F550);
+    }
+
+    #[test]
+    fn time_zones_can_put_now_on_another_day() {
+        let utc = date("2025-03-28").and_hms_opt(16, 0, 0).unwrap().and_utc();
+        let in_zone = |tz: Tz| day_of(utc.with_timezone(&tz).naive_local());
+
+        assert_eq!(in_zone(chrono_tz::UTC), date("2025-03-28"));
+        assert_eq!(in_zone(chrono_tz::Pacific::Kiritimati), date("2025-03-29"));
+    }
+
+    #[test]
+    fn round_time_never_overlaps_neighbours() {
+        assert_eq!(round_time(t(9, 7), dur(5)), t(9, 5));
+        assert_eq!(round_time(t(9, 8), dur(5)), t(9, 10));
+        assert_eq!(round_time(t(9, 7), Duration::zero()), t(9, 7));
+
+        let boundary = t(9, 7) + Duration::seconds(30);
+        assert!(round_time(t(9, 2), dur(5)) <= round_time(boundary, dur(5)));
+    }
+
+    #[test]
+    fn writing_the_current_slot_needs_no_home_dir() {
+        let slot = scheduled(dur(60), dur(60));
+        write_slot(None, &slot);
+
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_slot(Some(&dir), &slot);
+        let written = std::fs::read_to_string(dir.join(".current_task")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written, "...");
+    }
+
+    #[test]
+    fn days_fall_back_to_the_home_dir_without_a_data_dir() {
+        let system = PathBuf::from("/home/user/.local/share");
+        let home = PathBuf::from("/tmp/home");
+
+        assert_eq!(data_dir(None, Some(&home)), Some(home.clone()));
+        assert_eq!(data_dir(Some(system.clone()), Some(&home)), Some(system));
+        assert_eq!(data_dir(None, None), None);
+    }
+
+    #[test]
+    fn overrun_is_how_far_the_last_slot_ends_past_the_window() {
+        let window = PlanWindow {
+            start: t(7, 0),
+            total: dur(180),
+        };
+
+        assert_eq!(overrun(&[scheduled(dur(60), dur(45))], window), None);
+        assert_eq!(
+            overrun(&[scheduled(dur(60), dur(80))], window),
+            Some(dur(20))
+        );
+        assert_eq!(overrun(&[], window), None);
+    }
+
+    #[test]
+    fn status_has_the_slot_times_and_time_left() {
+        let slot = scheduled(dur(60), dur(60));
+        let status = serde_json::to_value(SlotStatus::new(&slot, t(9, 15))).unwrap();
+
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "name": "...",
+                "start": "09:00",
+                "end": "10:00",
+                "remaining_secs": 45 * 60,
+            })
+        );
+    }
+
+    #[test]
+    fn scrolling_keeps_the_cursor_on_screen() {
+        let slots = vec![scheduled(dur(60), dur(60)); 30];
+
+        assert_eq!(scroll_into_view(&slots, 0, 5, 10), 0);
+        assert_eq!(scroll_into_view(&slots, 0, 15, 10), 6);
+        assert_eq!(scroll_into_view(&slots, 6, 3, 10), 3);
+        assert_eq!(scroll_into_view(&slots, 6, 29, 1), 29);
+
+        let mut blocks = slots.clone();
+        blocks[15].block = 1;
+        assert_eq!(scroll_into_view(&blocks, 0, 15, 10), 7);
+
+        let mut notes = slots.clone();
+        notes[15].configured.note = Some("agenda".to_string());
+        assert_eq!(scroll_into_view(&notes, 0, 15, 10), 7);
+    }
+
+    #[test]
+    fn day_label_is_saved_and_cleared() {
+        let reload = |day: &Day| -> Day {
+            serde_json::from_str(&serde_json::to_string(day).unwrap()).unwrap()
+        };
+        let mut day = Day::default_with_id(date("2025-03-28"));
+
+        day.set_label(" Conference day ");
+        assert_eq!(reload(&day).label.as_deref(), Some("Conference day"));
+
+        day.set_label("");
+        assert_eq!(reload(&day).label, None);
+        assert!(!serde_json::to_string(&day).unwrap().contains("label"));
+    }
+
+    #[test]
+    fn cursor_skips_hidden_columns() {
+        let hidden = [Field::Requested];
+        let mut cursor = Cursor {
+            index: 0,
+            field: Field::End,
+        };
+
+        cursor.right(&hidden);
+        assert!(cursor.field == Field::Length);
+        cursor.left(&hidden);
+        assert!(cursor.field == Field::End);
+
+        cursor.field = Field::Weight;
+        cursor.right(&hidden);
+        assert!(cursor.field == Field::Weight);
+        cursor.field = Field::Name;
+        cursor.left(&hidden);
+        assert!(cursor.field == Field::Name);
+    }
+
+    #[test]
+    fn days_without_a_version_are_migrated() {
+        let day = Day::from_json(r#"{"day": "2025-03-28", "slots_config": []}"#).unwrap();
+
+        assert_eq!(day.schema_version, SCHEMA_VERSION);
+        assert!(serde_json::to_string(&day)
+            .unwrap()
+            .contains(r#""schema_version":1"#));
+    }
+
+    #[test]
+    fn title_shows_the_minutes_left() {
+        let mut slot = scheduled(dur(60), dur(60));
+        slot.configured.name = "code review".to_string();
+
+        assert_eq!(slot_title(&slot, t(9, 35)), "code review — 25m left");
+        assert_eq!(
+            slot_title(&slot, t(9, 59) + Duration::seconds(30)),
+            "code review — 1m left"
+        );
+
+        slot.configured.kind = SlotKind::Break;
+        assert_eq!(slot_title(&slot, t(9, 0)), "break — 60m left");
+    }
+
+    #[test]
+    fn banners_are_added_removed_and_saved() {
+        let reload = |day: &Day| -> Day {
+            serde_json::from_str(&serde_json::to_string(day).unwrap()).unwrap()
+        };
+        let mut day = Day::default_with_id(date("2025-03-28"));
+
+        day.add_banner("on-call");
+        day.add_banner(" ");
+        day.add_banner("travel");
+        assert_eq!(reload(&day).banners, vec!["on-call", "travel"]);
+
+        day.remove_banner(0);
+        day.remove_banner(5);
+        assert_eq!(reload(&day).banners, vec!["travel"]);
+
+        day.remove_banner(0);
+        assert!(reload(&day).banners.is_empty());
+        assert!(!serde_json::to_string(&day).unwrap().contains("banners"));
+    }
+
+    #[test]
+    fn edits_with_starts_outside_the_window_are_undone() {
+        let mut day = Day::default_with_id(date("2025-03-28"));
+        day.insert(0, &SlotDefaults::default());
+        day.insert(1, &SlotDefaults::default());
+        let set_start = |start| {
+            move |day: &mut Day| {
+                let mut slot = day.slots_config[1].clone();
+                slot.config.start = Some(start);
+                day.slots_config.over_ride(1, slot);
+            }
+        };
+
+        assert_eq!(edit_within_window(&mut day, set_start(t(12, 0))), Ok(()));
+        assert_eq!(edit_within_window(&mut day, set_start(t(23, 30))), Err(1));
+        assert_eq!(day.slots_config[1].config.start, Some(t(12, 0)));
+    }
+
+    /// Makes `now` return `time` for the rest of the test.
+    fn pin_now(time: &str) {
+        let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap();
+        PINNED_NOW.set(Some(time));
+    }
+
+    #[test]
+    fn past_midnight_is_before_the_day_offset() {
+        let at = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+
+        assert!(is_past_midnight(at(2)));
+        assert!(!is_past_midnight(at(4)));
+
+        pin_now("2025-03-29 02:00");
+        assert_eq!(current_day(), date("2025-03-28"));
+        assert_eq!(current_time(), t(26, 0));
+
+        pin_now("2025-03-29 04:00");
+        assert_eq!(current_day(), date("2025-03-29"));
+        assert_eq!(current_time(), t(4, 0));
+    }
+
+    #[test]
+    fn active_slot_is_the_one_now_falls_in() {
+        let slots = calculate_slots(
+            t(7, 0),
+            dur(16 * 60),
+            vec![SlotDto::default(), SlotDto::default()],
+        );
+
+        pin_now("2025-03-28 14:59");
+        assert_eq!(active_index(&slots, current_time()), Some(0));
+        pin_now("2025-03-28 15:00");
+        assert_eq!(active_index(&slots, current_time()), Some(1));
+        pin_now("2025-03-29 01:00");
+        assert_eq!(active_index(&slots, current_time()), None);
+    }
+
+    #[test]
+    fn filling_lasts_until_the_next_fixed_start() {
+        let mut meeting = SlotDto::default();
+        meeting.config.start = Some(t(14, 0));
+        let slots = calculate_slots(
+            t(7, 0),
+            dur(16 * 60),
+            vec![
+                SlotDto::default(),
+                SlotDto::default(),
+                meeting,
+                SlotDto::default(),
+            ],
+        );
+
+        // The first two share 07:00 to 14:00, the last two 14:00 to 23:00.
+        assert_eq!(fill_length(&slots, 1, t(23, 0)), dur(210));
+        assert_eq!(fill_length(&slots, 2, t(23, 0)), dur(9 * 60));
+        assert_eq!(fill_length(&slots, 3, t(23, 0)), dur(270));
+    }
+
+    #[test]
+    fn long_names_are_cut_with_an_ellipsis() {
+        assert_eq!(truncate("standup", 10), "standup");
+        assert_eq!(truncate("standup", 7), "standup");
+        assert_eq!(truncate("quarterly planning", 10), "quarterly…");
+        assert_eq!(truncate("smørbrød", 4), "smø…");
+    }
+
+    #[test]
+    fn names_narrow_to_fit_the_terminal() {
+        let rest = row_width_without_name(&[]);
+        assert_eq!(rest, 74);
+        assert_eq!(row_width_without_name(&[Field::Requested]), 56);
+
+        assert_eq!(fit_name_width(20, rest, 100), 20);
+        assert_eq!(fit_name_width(20, rest, 90), 16);
+        assert_eq!(fit_name_width(20, rest, 60), 4);
+        assert_eq!(fit_name_width(20, rest, usize::MAX), 20);
+    }
+
+    #[test]
+    fn starts_can_be_relative_to_now() {
+        let now = t(9, 40);
+
+        assert_eq!(parse_start("+30", now), Some((t(10, 10), false)));
+        assert_eq!(parse_start("+1h", now), Some((t(10, 40), false)));
+        assert_eq!(parse_start("~+15", now), Some((t(9, 55), true)));
+        assert_eq!(parse_start("13:00", now), Some((t(13, 0), false)));
+        assert_eq!(parse_start("+", now), None);
+        assert_eq!(parse_start("+soon", now), None);
+    }
+
+    /// Held by tests that read day files, since they share the notices left for the planner.
+    static CORRUPT_DAYS_TEST: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn corrupt_day_files_are_set_aside() {
+        let _guard = CORRUPT_DAYS_TEST.lock().unwrap();
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2025-03-28");
+        std::fs::write(&path, r#"{"day": "2025-03-28", "slots_con"#).unwrap();
+
+        let day = read_day_file(&path);
+        let moved = dir.join("2025-03-28.corrupt").exists();
+        let notice = corrupt_days_notice();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(day.is_none());
+        assert!(!path.exists());
+        assert!(moved);
+        assert!(notice.unwrap().contains("2025-03-28.corrupt"));
+    }
+
+    #[test]
+    fn day_files_that_arent_text_are_set_aside() {
+        let _guard = CORRUPT_DAYS_TEST.lock().unwrap();
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2025-03-28");
+        std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+
+        let day = read_day_file(&path);
+        let moved = dir.join("2025-03-28.corrupt").exists();
+        let notice = corrupt_days_notice();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(day.is_none());
+        assert!(moved);
+        assert!(notice.is_some());
+    }
+
+    #[test]
+    fn a_day_file_that_cant_be_moved_is_reported() {
+        let _guard = CORRUPT_DAYS_TEST.lock().unwrap();
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("2025-03-28.corrupt").join("taken")).unwrap();
+        let path = dir.join("2025-03-28");
+        std::fs::write(&path, "{").unwrap();
+
+        let day = read_day_file(&path);
+        let kept = path.exists();
+        let notice = corrupt_days_notice();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(day.is_none());
+        assert!(kept);
+        assert!(notice.unwrap().contains("failed"));
+    }
+
+    #[test]
+    fn selected_slots_all_get_the_length() {
+        let mut slots = SlotDtos::default();
+        for _ in 0..4 {
+            slots.insert(0, SlotDto::default());
+        }
+
+        set_lengths(&mut slots, 1..=3, dur(30));
+        let lengths: Vec<Duration> = slots.iter().map(|slot| slot.config.length).collect();
+        assert_eq!(
+            lengths,
+            vec![SlotDto::default().config.length, dur(30), dur(30), dur(30)]
+        );
+    }
+
+    #[test]
+    fn inserted_slots_use_the_configured_defaults() {
+        let mut day = Day::default_with_id(date("2025-03-28"));
+        let defaults = SlotDefaults {
+            name: "focus".to_string(),
+            length: dur(25),
+        };
+
+        day.insert(0, &defaults);
+        assert_eq!(day.slots_config[0].name, "focus");
+        assert_eq!(day.slots_config[0].config.length, dur(25));
+    }
+
+    #[test]
+    fn glyph_tells_events_from_tasks() {
+        let glyph = |start: bool, fixed_length: bool| {
+            let mut config = SlotDto::default().config;
+            config.start = start.then(|| t(9, 0));
+            config.fixed_length = fixed_length;
+            schedule_glyph(&config, false)
+        };
+
+        assert_eq!(glyph(true, true), '◆');
+        assert_eq!(glyph(true, false), '○');
+        assert_eq!(glyph(false, false), '○');
+        assert_eq!(glyph(false, true), ' ');
+    }
+
+    #[test]
+    fn without_notifications_only_the_current_task_is_written() {
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl NotificationSink for Recorder {
+            fn notify(&self, summary: &str, _id: u32) {
+                self.0.lock().unwrap().push(summary.to_string());
+            }
+        }
+
+        let slot = scheduled(dur(60), dur(60));
+        let recorder = Recorder::default();
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        on_new_slot(Some(&dir), &slot, false, &recorder);
+        let written = dir.join(".current_task").exists();
+        on_new_slot(Some(&dir), &slot, true, &recorder);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(written);
+        assert_eq!(*recorder.0.lock().unwrap(), vec!["new task: ..."]);
+    }
+
+    #[test]
+    fn categories_add_up_their_scheduled_time() {
+        let slot = |category: Option<&str>, length| {
+            let mut slot = scheduled(dur(60), dur(length));
+            slot.configured.category = category.map(str::to_string);
+            slot
+        };
+        let day = [
+            slot(Some("work"), 90),
+            slot(Some("health"), 30),
+            slot(None, 45),
+            slot(Some("work"), 120),
+            slot(Some("chores"), 30),
+        ];
+
+        assert_eq!(
+            category_totals(&day),
+            vec![
+                ("work".to_string(), dur(210)),
+                ("chores".to_string(), dur(30)),
+                ("health".to_string(), dur(30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_changes_mark_a_loaded_day_dirty() {
+        let day = LoadedDay::new(Day::default_with_id(date("2025-03-28")), false);
+
+        // Moving the cursor around only reads the day.
+        let _ = day.read().slots();
+        assert!(!day.is_dirty());
+
+        let (before, _) = edit_slots(&day, |_| {});
+        assert!(before.is_none());
+        assert!(!day.is_dirty());
+
+        let (before, _) = edit_slots(&day, |day| {
+            day.insert(0, &SlotDefaults::default());
+        });
+        assert!(before.is_some());
+        assert!(day.is_dirty());
+    }
+
+    #[test]
+    fn edits_are_written_before_the_next_key() {
+        let id = date("2191-05-17");
+        let mut app = App::start(Args {
+            date: Some(id),
+            ..Default::default()
+        });
+        let _ = edit_slots(&app.selected_day, |day| {
+            day.insert(0, &SlotDefaults::default());
+        });
+
+        let _ = app.apply(Action::ToggleDone);
+        let saved = Day::load(id);
+        Day::delete(id);
+
+        assert!(saved.unwrap().slots_config[0].done);
+    }
+
+    #[test]
+    fn readonly_leaves_new_days_unwritten() {
+        let id = date("2191-05-18");
+        let mut app = App::start(Args {
+            date: Some(id),
+            readonly: true,
+            ..Default::default()
+        });
+
+        let _ = app.apply(Action::Up);
+        app.flush_all();
+
+        assert!(Day::load(id).is_none());
+    }
 }
@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use notify_rust::Notification;
+
+/// Set to turn off notifications.
+const DISABLE_ENV: &str = "DAGPLAN_NO_NOTIFY";
+
+/// Set to use [`Mako`] when mako is running. Off by default, as it dismisses other programs'
+/// notifications too.
+const MAKO_ENV: &str = "DAGPLAN_MAKO_DISMISS";
+
+/// Notifications closer together than this many seconds are treated as one burst.
+const BURST_SECS: u64 = 10;
+
+/// Somewhere to show notifications. Notifications with the same id replace each other where the
+/// backend supports it.
+pub trait NotificationSink: Send + Sync {
+    fn notify(&self, summary: &str, id: u32);
+
+    /// Removes every notification on screen, where the backend can.
+    fn dismiss_all(&self) {}
+}
+
+/// Plain desktop notifications through notify-rust. A notification replaces the previous one with
+/// the same id in place where the daemon supports it, and simply stacks up elsewhere.
+pub struct Desktop;
+
+impl NotificationSink for Desktop {
+    fn notify(&self, summary: &str, id: u32) {
+        let _ = Notification::new().summary(summary).id(id).show();
+    }
+}
+
+/// Desktop notifications for the mako daemon, which can't replace them in place. Used through
+/// [`Burst`] when opted into with `DAGPLAN_MAKO_DISMISS`.
+pub struct Mako;
+
+impl NotificationSink for Mako {
+    fn notify(&self, summary: &str, id: u32) {
+        Desktop.notify(summary, id);
+    }
+
+    /// Dismisses other processes' notifs too, hence the opt-in.
+    fn dismiss_all(&self) {
+        let _ = std::process::Command::new("makoctl")
+            .arg("dismiss")
+            .output();
+    }
+}
+
+/// Clears `inner` before a notification that's part of the same burst as the last one, to avoid
+/// a pile of them at the same time.
+pub struct Burst<S> {
+    inner: S,
+    /// Unix time of the last notification, in seconds.
+    last: AtomicU64,
+}
+
+impl<S> Burst<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            last: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<S: NotificationSink> NotificationSink for Burst<S> {
+    fn notify(&self, summary: &str, id: u32) {
+        let now = current_unix_time();
+        let prev = self.last.swap(now, Ordering::SeqCst);
+        if in_burst(prev, now) {
+            self.inner.dismiss_all();
+        }
+
+        self.inner.notify(summary, id);
+    }
+}
+
+/// Drops every notification.
+pub struct Silent;
+
+impl NotificationSink for Silent {
+    fn notify(&self, _summary: &str, _id: u32) {}
+}
+
+static SINK: OnceLock<Box<dyn NotificationSink>> = OnceLock::new();
+
+/// Shows a notification through the sink that suits this system.
+pub fn send(summary: &str, id: u32) {
+    sink().notify(summary, id);
+}
+
+/// The sink that suits this system.
+pub fn sink() -> &'static dyn NotificationSink {
+    SINK.get_or_init(detect).as_ref()
+}
+
+/// Whatever notify-rust talks to, or mako if that was opted into and its `makoctl` is around.
+fn detect() -> Box<dyn NotificationSink> {
+    if std::env::var_os(DISABLE_ENV).is_some() {
+        Box::new(Silent)
+    } else if std::env::var_os(MAKO_ENV).is_some()
+        && cfg!(target_os = "linux")
+        && on_path("makoctl")
+    {
+        Box::new(Burst::new(Mako))
+    } else {
+        Box::new(Desktop)
+    }
+}
+
+fn on_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+}
+
+/// Whether a notification at `now` comes soon enough after the one at `prev` to be in the same
+/// burst. Both are unix times in seconds.
+fn in_burst(prev: u64, now: u64) -> bool {
+    now.saturating_sub(prev) < BURST_SECS
+}
+
+fn current_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Keeps what's on screen, as a daemon that can dismiss would.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl NotificationSink for Recorder {
+        fn notify(&self, summary: &str, _id: u32) {
+            self.0.lock().unwrap().push(summary.to_string());
+        }
+
+        fn dismiss_all(&self) {
+            self.0.lock().unwrap().clear();
+        }
+    }
+
+    #[test]
+    fn only_close_notifications_are_a_burst() {
+        let now = current_unix_time();
+
+        assert!(in_burst(now - 3, now));
+        assert!(!in_burst(now - BURST_SECS, now));
+        // Nothing sent yet.
+        assert!(!in_burst(0, now));
+        // The clock went back.
+        assert!(in_burst(now + 5, now));
+    }
+
+    #[test]
+    fn a_repeat_within_a_burst_replaces_the_last() {
+        let burst = Burst::new(Recorder::default());
+        burst.notify("5 minutes left", 6007);
+        burst.notify("5 minutes left", 6007);
+        assert_eq!(*burst.inner.0.lock().unwrap(), vec!["5 minutes left"]);
+
+        burst
+            .last
+            .store(current_unix_time() - BURST_SECS, Ordering::SeqCst);
+        burst.notify("new task: review", 6007);
+        assert_eq!(burst.inner.0.lock().unwrap().len(), 2);
+    }
+}
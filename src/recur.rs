@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use dagplan::slot::{SlotDto, SlotDtos};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use vedvaring::FsTrait;
+
+use crate::profile_root;
+
+/// Which days a recurring slot comes back on.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+}
+
+impl Recurrence {
+    pub fn includes(self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekdays => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+        }
+    }
+}
+
+/// A slot that's put into every new day its recurrence includes, like a daily standup.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Recurring {
+    pub id: Uuid,
+    pub slot: SlotDto,
+    pub every: Recurrence,
+}
+
+impl FsTrait for Recurring {
+    type Key = Uuid;
+
+    fn item_id(&self) -> Self::Key {
+        self.id
+    }
+
+    fn root() -> PathBuf {
+        profile_root(Self::crate_name())
+    }
+}
+
+/// The slots a new day on `date` starts out with, ordered by their starts. Slots without a
+/// start come first.
+pub fn slots_for(date: NaiveDate, recurring: &[Recurring]) -> SlotDtos {
+    let mut due: Vec<&SlotDto> = recurring
+        .iter()
+        .filter(|recurring| recurring.every.includes(date))
+        .map(|recurring| &recurring.slot)
+        .collect();
+    due.sort_by_key(|slot| slot.config.start);
+
+    let mut slots = SlotDtos::default();
+    for slot in due {
+        slots.insert(slots.len(), slot.clone());
+    }
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dagplan::slot::t;
+
+    fn recurring(name: &str, every: Recurrence, hour: u32) -> Recurring {
+        let mut slot = SlotDto {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        slot.config.start = Some(t(hour, 0));
+        Recurring {
+            id: Uuid::new_v4(),
+            slot,
+            every,
+        }
+    }
+
+    #[test]
+    fn weekday_slots_only_come_back_on_weekdays() {
+        let recurring = [
+            recurring("walk", Recurrence::Daily, 12),
+            recurring("standup", Recurrence::Weekdays, 9),
+        ];
+        let names = |date: &str| -> Vec<String> {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+            slots_for(date, &recurring)
+                .iter()
+                .map(|slot| slot.name.clone())
+                .collect()
+        };
+
+        // A Friday, then a Saturday.
+        assert_eq!(names("2025-03-28"), vec!["standup", "walk"]);
+        assert_eq!(names("2025-03-29"), vec!["walk"]);
+    }
+}
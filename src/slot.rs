@@ -1,10 +1,10 @@
 use chrono::Duration;
-use crossterm::terminal::disable_raw_mode;
 use nonempty::NonEmpty;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::{Debug, Display};
 use std::mem;
+use std::ops::Deref;
 use uuid::Uuid;
 
 type ActId = Uuid;
@@ -21,35 +21,163 @@ pub fn calculate_slots(
     TimeSlotConfig::calculate_slots(start_time, total_time, configs)
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, Hash, Eq, PartialEq)]
+/// Like [`calculate_slots`], but fails with the first slot's warning if any slot got one.
+pub fn try_calculate_slots(
+    start_time: TimeSinceMidnight,
+    total_time: Duration,
+    configs: Vec<SlotDto>,
+) -> Result<Vec<SlotResult>, ScheduleError> {
+    let slots = calculate_slots(start_time, total_time, configs);
+
+    if let Some(err) = slots.iter().find_map(|slot| slot.warning.clone().err()) {
+        return Err(err);
+    }
+
+    Ok(slots)
+}
+
+/// How the time of each block is shared out, in block order, for seeing why a slot got squeezed.
+pub fn block_allocations(
+    start_time: TimeSinceMidnight,
+    total_time: Duration,
+    configs: Vec<SlotDto>,
+) -> Vec<SlotAllocTime> {
+    let start_time = configs
+        .first()
+        .and_then(|x| x.config.start)
+        .unwrap_or(start_time);
+    let Some(configs) = NonEmpty::from_vec(configs) else {
+        return vec![];
+    };
+
+    get_slotblocks(start_time, total_time, configs)
+        .into_iter()
+        .map(|block| block.get_allocated())
+        .collect()
+}
+
+/// The blocks whose fixed lengths and elastic minimums need more time than the block has, as
+/// the block's start and how much too much is asked of it.
+pub fn overcommitted_blocks(
+    start_time: TimeSinceMidnight,
+    total_time: Duration,
+    configs: Vec<SlotDto>,
+) -> Vec<(TimeSinceMidnight, Duration)> {
+    let start_time = configs
+        .first()
+        .and_then(|x| x.config.start)
+        .unwrap_or(start_time);
+    let Some(configs) = NonEmpty::from_vec(configs) else {
+        return vec![];
+    };
+
+    get_slotblocks(start_time, total_time, configs)
+        .into_iter()
+        .filter_map(|block| {
+            let over = block.get_allocated().overcommitted_by(&block.slots);
+            (over > Duration::zero()).then_some((block.start, over))
+        })
+        .collect()
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct SlotDto {
     pub name: String,
     pub act: Option<ActId>,
     pub config: TimeSlotConfig,
+    #[serde(default)]
+    pub kind: SlotKind,
+    #[serde(default)]
+    pub done: bool,
+    /// Free text shown under the slot, like a meeting's agenda.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// What the slot counts towards, like "work" or "health", for colors and totals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 impl Default for SlotDto {
     fn default() -> Self {
         Self {
-            name: format!("..."),
+            name: "...".to_string(),
             act: Default::default(),
             config: Default::default(),
+            kind: Default::default(),
+            done: false,
+            note: None,
+            category: None,
         }
     }
 }
 
+impl SlotDto {
+    /// Whether the slot asks to keep its requested length.
+    fn is_fixed(&self) -> bool {
+        self.kind == SlotKind::Activity && self.config.fixed_length
+    }
+
+    /// Whether the slot shrinks or grows with the time left over in its block.
+    fn is_elastic(&self) -> bool {
+        self.kind != SlotKind::Break && !self.is_fixed()
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum SlotKind {
+    #[default]
+    Activity,
+    /// Takes up its requested length no matter how tight the block is, and never gets notified about.
+    Break,
+    /// Takes all the elastic time of its block, leaving nothing for the other elastic slots.
+    Filler,
+}
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum ScheduleError {
     NoElasticSlots,
     InsufficientFixedTime,
+    /// The computed length came out negative or undefined, so the slot was squashed to nothing.
+    InvalidLength,
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ScheduleError::NoElasticSlots => "fixed slots stretched, no elastic slots",
+            ScheduleError::InsufficientFixedTime => "fixed slots don't fit",
+            ScheduleError::InvalidLength => "slot length came out invalid",
+        };
+
+        write!(f, "{s}")
+    }
 }
 
+impl std::error::Error for ScheduleError {}
+
 /// The configuration for when a slot should be. Doesn't mean it will be on that time that depends on its constraints
-#[derive(Clone, Serialize, Deserialize, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct TimeSlotConfig {
     pub start: Option<TimeSinceMidnight>,
     pub length: Duration,
     pub fixed_length: bool,
+    /// Elastic slots won't shrink below this
+    #[serde(default)]
+    pub min_length: Option<Duration>,
+    /// Elastic slots won't grow beyond this
+    #[serde(default)]
+    pub max_length: Option<Duration>,
+    /// How big a share of the block's slack an elastic slot gets, relative to its requested length
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// A preferred start, kept only if the slots before it fit in their requested lengths and it
+    /// isn't past a later fixed start. Otherwise the slot slides like one without a start.
+    #[serde(default)]
+    pub soft_start: Option<TimeSinceMidnight>,
+}
+
+fn default_weight() -> f32 {
+    1.0
 }
 
 impl Default for TimeSlotConfig {
@@ -58,6 +186,10 @@ impl Default for TimeSlotConfig {
             start: Default::default(),
             length: Duration::hours(1),
             fixed_length: Default::default(),
+            min_length: None,
+            max_length: None,
+            weight: default_weight(),
+            soft_start: None,
         }
     }
 }
@@ -75,29 +207,25 @@ impl TimeSlotConfig {
         let slotblocks = get_slotblocks(start_time, total_time, configs);
         let mut out: Vec<SlotResult> = vec![];
 
-        for block in slotblocks {
-            dbg!();
-            dbg!(&block);
-            let res = block.get_slot_result();
-            dbg!(&res);
-            out.extend(res);
+        for (idx, block) in slotblocks.into_iter().enumerate() {
+            out.extend(block.get_slot_result(idx));
         }
 
         out
     }
 }
 
-use humantime;
-
 use crate::TimeSinceMidnight;
 
 /// The calculated start and length time of a slot after having to fit within constraints
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Clone)]
 pub struct SlotResult {
     pub start: TimeSinceMidnight,
     pub length: Duration,
     pub warning: Result<(), ScheduleError>,
     pub configured: SlotDto,
+    /// Which block the slot was scheduled in, counting from 0. A new block begins at each fixed start.
+    pub block: usize,
 }
 
 impl Display for SlotResult {
@@ -125,8 +253,8 @@ impl Debug for SlotResult {
     }
 }
 
-struct SlotAllocTime {
-    /// Total time allocated to the block. All slots summed up should fit this.
+pub struct SlotAllocTime {
+    /// Total time allocated to the block, minus its breaks. All non-break slots summed up should fit this.
     tot_alloc: Duration,
     /// The sum of all the fixed lengths in a block
     tot_req_fixed: Duration,
@@ -160,6 +288,11 @@ impl SlotAllocTime {
             return None;
         };
 
+        // Fixed lengths that fill the block to within a minute, like a frozen block's, fit as they are.
+        if (self.tot_alloc - self.tot_req_fixed).abs() < Duration::minutes(1) {
+            return None;
+        }
+
         let no_elastic_slots = self.tot_req_elastic.is_zero();
         let too_little_time_alloc = self.tot_req_fixed > self.tot_alloc;
 
@@ -178,12 +311,104 @@ impl SlotAllocTime {
         }
     }
 
-    /// How much the elastic slots should be modified
-    fn elastic_ratio(&self) -> f32 {
+    /// How much more time the fixed slots and the elastic minimums ask for than the block has.
+    /// Zero or negative when everything fits.
+    fn overcommitted_by(&self, slots: &NonEmpty<SlotDto>) -> Duration {
+        let min_elastic: Duration = slots
+            .iter()
+            .filter(|slot| slot.is_elastic())
+            .filter_map(|slot| slot.config.min_length)
+            .sum();
+
+        self.tot_req_fixed + min_elastic - self.tot_alloc
+    }
+
+    /// The length in seconds of each of the given elastic slots.
+    ///
+    /// The elastic time is shared out proportionally to the weighted requested lengths. Slots that would end up
+    /// outside their min/max bounds get pinned to the bound and the remaining time is shared out again
+    /// between the others, until no more slots need pinning. Each pass only pins to the min bounds or
+    /// the max bounds, whichever is short of time, so no time freed by the other is left over.
+    ///
+    /// Fillers don't take part in that. When there are any, they split all the elastic time evenly between them.
+    fn elastic_lengths(&self, slots: &[&SlotDto]) -> Vec<f32> {
         if self.fixed_ratio().is_some() {
-            0.
-        } else {
-            self.elastic_alloc_time.num_seconds() as f32 / self.tot_req_elastic.num_seconds() as f32
+            return vec![0.; slots.len()];
+        }
+
+        let secs = |dur: Duration| dur.num_seconds() as f32;
+
+        let is_filler = |slot: &SlotDto| slot.kind == SlotKind::Filler;
+        let fillers = slots.iter().filter(|slot| is_filler(slot)).count();
+        if fillers > 0 {
+            let share = secs(self.elastic_alloc_time) / fillers as f32;
+            return slots
+                .iter()
+                .map(|slot| if is_filler(slot) { share } else { 0. })
+                .collect();
+        }
+
+        let slots: Vec<&TimeSlotConfig> = slots.iter().map(|slot| &slot.config).collect();
+        let mut pinned: Vec<Option<f32>> = vec![None; slots.len()];
+
+        loop {
+            let pinned_time: f32 = pinned.iter().flatten().sum();
+            let free_req_time: f32 = slots
+                .iter()
+                .zip(&pinned)
+                .filter(|(_, pin)| pin.is_none())
+                .map(|(slot, _)| secs(slot.length) * slot.weight)
+                .sum();
+            let free_time = (secs(self.elastic_alloc_time) - pinned_time).max(0.);
+            let ratio = free_time / free_req_time;
+
+            let min = |slot: &TimeSlotConfig, length: f32| {
+                slot.min_length.map(secs).filter(|min| length < *min)
+            };
+            let max = |slot: &TimeSlotConfig, length: f32| {
+                slot.max_length.map(secs).filter(|max| length > *max)
+            };
+
+            // How much pinning every slot out of bounds would add to the total. Only the bound
+            // that's short of time gets pinned in a pass, so time freed by the other one is shared
+            // out again instead of being left over.
+            let excess: f32 = slots
+                .iter()
+                .zip(&pinned)
+                .filter(|(_, pin)| pin.is_none())
+                .map(|(slot, _)| {
+                    let length = secs(slot.length) * slot.weight * ratio;
+                    min(slot, length)
+                        .or(max(slot, length))
+                        .map_or(0., |pin| pin - length)
+                })
+                .sum();
+
+            let mut newly_pinned = false;
+
+            for (slot, pin) in slots.iter().zip(pinned.iter_mut()) {
+                if pin.is_some() {
+                    continue;
+                }
+
+                let length = secs(slot.length) * slot.weight * ratio;
+
+                if let Some(min) = min(slot, length).filter(|_| excess >= 0.) {
+                    *pin = Some(min);
+                    newly_pinned = true;
+                } else if let Some(max) = max(slot, length).filter(|_| excess <= 0.) {
+                    *pin = Some(max);
+                    newly_pinned = true;
+                }
+            }
+
+            if !newly_pinned {
+                return slots
+                    .iter()
+                    .zip(pinned)
+                    .map(|(slot, pin)| pin.unwrap_or(secs(slot.length) * slot.weight * ratio))
+                    .collect();
+            }
         }
     }
 }
@@ -226,27 +451,24 @@ impl SlotBlock {
     }
 
     fn get_allocated(&self) -> SlotAllocTime {
-        assert!(self.end_time > self.start);
-
-        let tot_alloc = self.end_time - self.start;
+        let tot_req_break: Duration = self
+            .slots
+            .iter()
+            .filter(|slot| slot.kind == SlotKind::Break)
+            .map(|slot| slot.config.length)
+            .sum();
+        let tot_alloc = (self.end_time - self.start - tot_req_break).max(Duration::zero());
         let tot_req_fixed: Duration = self
             .slots
             .iter()
-            .map(|slot| {
-                slot.config
-                    .fixed_length
-                    .then_some(slot.config.length)
-                    .unwrap_or_default()
-            })
+            .filter(|slot| slot.is_fixed())
+            .map(|slot| slot.config.length)
             .sum();
         let tot_req_elastic: Duration = self
             .slots
             .iter()
-            .map(|slot| {
-                (!slot.config.fixed_length)
-                    .then_some(slot.config.length)
-                    .unwrap_or_default()
-            })
+            .filter(|slot| slot.is_elastic())
+            .map(|slot| slot.config.length)
             .sum();
         let elastic_alloc_time = tot_alloc.checked_sub(&tot_req_fixed).unwrap_or_default();
 
@@ -258,7 +480,7 @@ impl SlotBlock {
         }
     }
 
-    fn get_slot_result(self) -> Vec<SlotResult> {
+    fn get_slot_result(self, block: usize) -> Vec<SlotResult> {
         let mut out: Vec<SlotResult> = vec![];
 
         let alloc = self.get_allocated();
@@ -267,25 +489,40 @@ impl SlotBlock {
             .fixed_ratio()
             .map(|(ratio, warn)| (ratio, Err(warn)))
             .unwrap_or((1.0, Ok(())));
-        let elastic_ratio = alloc.elastic_ratio();
-
-        dbg!(&alloc, fixed_ratio, &fix_warn, elastic_ratio);
+        let elastic_slots: Vec<&SlotDto> =
+            self.slots.iter().filter(|slot| slot.is_elastic()).collect();
+        let elastic_lengths = alloc.elastic_lengths(&elastic_slots);
 
+        let mut elastic_lengths = elastic_lengths.into_iter();
         let mut start = self.start;
 
         for slot in self.slots {
-            let fixed = slot.config.fixed_length;
-            let length = slot.config.length.num_seconds() as f32
-                * if fixed { fixed_ratio } else { elastic_ratio };
+            let fixed = slot.is_fixed();
+            let is_break = slot.kind == SlotKind::Break;
+            let length = if is_break {
+                slot.config.length.num_seconds() as f32
+            } else if fixed {
+                slot.config.length.num_seconds() as f32 * fixed_ratio
+            } else {
+                elastic_lengths.next().unwrap()
+            };
+
+            // Elastic slots get no time while the fixed ones don't fit, so they share their warning.
+            let (length, warning) = match secs_to_duration(length) {
+                Some(length) if !is_break => (length, fix_warn.clone()),
+                Some(length) => (length, Ok(())),
+                None => (Duration::zero(), Err(ScheduleError::InvalidLength)),
+            };
 
             let slot = SlotResult {
                 start,
-                length: Duration::from_std(std::time::Duration::from_secs_f32(length)).unwrap(),
-                warning: if fixed { fix_warn.clone() } else { Ok(()) },
+                length,
+                warning,
                 configured: slot,
+                block,
             };
 
-            start = start + slot.length;
+            start += slot.length;
             out.push(slot);
         }
 
@@ -293,29 +530,53 @@ impl SlotBlock {
     }
 }
 
+/// `None` if the seconds can't be a length, like when negative or NaN.
+fn secs_to_duration(secs: f32) -> Option<Duration> {
+    let secs = std::time::Duration::try_from_secs_f32(secs).ok()?;
+    Duration::from_std(secs).ok()
+}
+
 fn get_slotblocks(
     start_time: TimeSinceMidnight,
     total_time: Duration,
     configs: NonEmpty<SlotDto>,
 ) -> NonEmpty<SlotBlock> {
-    let cloned_configs = configs.clone();
-
     let mut blocks: Vec<SlotBlock> = vec![];
 
     let mut buf: Vec<SlotDto> = vec![];
     let mut configs: VecDeque<SlotDto> = configs.into_iter().collect();
+    let mut block_start = start_time;
+    let window_end = start_time + total_time;
 
     while let Some(config) = configs.pop_front() {
-        if let Some(start) = config.config.start {
-            if let Some(buf) = NonEmpty::from_vec(mem::take(&mut buf)) {
-                let start_time = match blocks.last() {
-                    Some(block) => block.end_time,
-                    None => start_time,
-                };
+        // A start before the block it would end is out of order, and one past the window is
+        // outside the day. Neither is an anchor, the UI flags them instead.
+        let hard_start = config
+            .config
+            .start
+            .filter(|start| *start >= block_start && *start <= window_end);
+        // A soft start only anchors when the slots before it get to keep their requested lengths,
+        // and never past the next fixed start, which would then be dropped without a warning.
+        let next_start = configs.iter().find_map(|slot| slot.config.start);
+        let soft_start = config.config.soft_start.filter(|start| {
+            let requested: Duration = buf.iter().map(|slot| slot.config.length).sum();
+            *start >= block_start + requested
+                && *start < window_end
+                && next_start.is_none_or(|next| *start <= next)
+        });
 
-                let block = SlotBlock::new(start_time, buf, start);
+        if let Some(start) = hard_start.or(soft_start) {
+            match NonEmpty::from_vec(mem::take(&mut buf)) {
+                Some(buf) => {
+                    let block = SlotBlock::new(block_start, buf, start);
 
-                blocks.push(block);
+                    blocks.push(block);
+                    block_start = start;
+                }
+                // Only the first slot has nothing before it. A fixed start there moves the whole
+                // window in calculate_slots instead, a soft one leaves a gap before it.
+                None if hard_start.is_none() => block_start = start,
+                None => {}
             }
         }
 
@@ -323,23 +584,14 @@ fn get_slotblocks(
     }
 
     if let Some(buf) = NonEmpty::from_vec(mem::take(&mut buf)) {
-        let block_start_time = match blocks.last() {
-            Some(block) => block.end_time,
-            None => start_time,
-        };
+        let block_start_time = block_start;
 
-        let end_time = start_time + total_time;
+        let end_time = window_end;
 
         if end_time < start_time {
-            disable_raw_mode().unwrap();
-            dbg!(&block_start_time, start_time, total_time);
-            dbg!(&end_time);
-
-            for config in cloned_configs {
-                dbg!(config);
-            }
-
-            panic!();
+            panic!(
+                "window ends before it starts: {block_start_time:?} {start_time:?} {total_time:?}"
+            );
         }
 
         let block = SlotBlock::new(block_start_time, buf, end_time);
@@ -358,3 +610,906 @@ pub fn t(h: u32, m: u32) -> TimeSinceMidnight {
 pub fn dur(mins: i64) -> Duration {
     Duration::minutes(mins)
 }
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct SlotDtos(Vec<SlotDto>);
+
+impl Deref for SlotDtos {
+    type Target = Vec<SlotDto>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl SlotDtos {
+    // Edits happen in place and get undone if they leave the start times out of order, rather
+    // than going through a validated copy of every slot.
+
+    pub fn insert(&mut self, index: usize, slot: SlotDto) {
+        let index = index.min(self.0.len());
+        self.0.insert(index, slot);
+
+        if !self.is_valid() {
+            self.0.remove(index);
+        }
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        if idx >= self.0.len() {
+            return;
+        }
+
+        let slot = self.0.remove(idx);
+
+        if !self.is_valid() {
+            self.0.insert(idx, slot);
+        }
+    }
+
+    pub fn unset_start(&mut self, idx: usize) {
+        if idx >= self.0.len() {
+            return;
+        }
+
+        let prev = self.0[idx].config.start.take();
+
+        if !self.is_valid() {
+            self.0[idx].config.start = prev;
+        }
+    }
+
+    pub fn set_start(&mut self, idx: usize, start: TimeSinceMidnight) {
+        if idx >= self.0.len() {
+            return;
+        }
+
+        let prev = self.0[idx].config.start.replace(start);
+
+        if !self.is_valid() {
+            self.0[idx].config.start = prev;
+        }
+    }
+
+    /// Sets the start of the slot and clears the starts of later slots that would come before it.
+    pub fn set_start_cascade(&mut self, idx: usize, start: TimeSinceMidnight) {
+        if idx >= self.0.len() {
+            return;
+        }
+
+        let prev: Vec<Option<TimeSinceMidnight>> =
+            self.0[idx..].iter().map(|slot| slot.config.start).collect();
+
+        self.0[idx].config.start = Some(start);
+        for slot in &mut self.0[idx + 1..] {
+            if slot.config.start.is_some_and(|later| later < start) {
+                slot.config.start = None;
+            }
+        }
+
+        if !self.is_valid() {
+            for (slot, start) in self.0[idx..].iter_mut().zip(prev) {
+                slot.config.start = start;
+            }
+        }
+    }
+
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.0.len();
+
+        if i >= len || j >= len {
+            return;
+        }
+
+        self.0.swap(i, j);
+
+        if !self.is_valid() {
+            self.0.swap(i, j);
+        }
+    }
+
+    pub fn over_ride(&mut self, index: usize, slot: SlotDto) {
+        if index >= self.0.len() {
+            return;
+        }
+
+        let prev = mem::replace(&mut self.0[index], slot);
+
+        if !self.is_valid() {
+            self.0[index] = prev;
+        }
+    }
+
+    /// Moves every fixed start by `by`, letting the other slots reflow around them. If a start
+    /// would end up outside the window, as checked by [`Self::validate_within`], nothing moves and
+    /// that slot's index is returned.
+    pub fn shift_starts(
+        &mut self,
+        by: Duration,
+        start_time: TimeSinceMidnight,
+        total_time: Duration,
+    ) -> Result<(), usize> {
+        let mut shifted = self.0.clone();
+        for slot in &mut shifted {
+            if let Some(start) = &mut slot.config.start {
+                *start += by;
+            }
+        }
+
+        Self::validate_within(&shifted, start_time, total_time)?;
+        self.0 = shifted;
+        Ok(())
+    }
+
+    /// Toggles whether slot `idx` has a fixed length. When it stops being fixed, it first requests
+    /// the length it was scheduled as in `results`, so it doesn't jump to a different length.
+    pub fn toggle_fixed_as_scheduled(&mut self, idx: usize, results: &[SlotResult]) {
+        let (Some(slot), Some(result)) = (self.0.get_mut(idx), results.get(idx)) else {
+            return;
+        };
+
+        if slot.config.fixed_length {
+            slot.config.length = result.length;
+        }
+        slot.config.fixed_length = !slot.config.fixed_length;
+    }
+
+    /// Moves `by` of the requested length of the slot after `idx` over to slot `idx`. If the next
+    /// slot would end up shorter than nothing, or there isn't one, nothing changes and its index is
+    /// returned.
+    pub fn borrow_from_next(&mut self, idx: usize, by: Duration) -> Result<(), usize> {
+        let next = idx + 1;
+        if idx >= self.0.len() || next >= self.0.len() || self.0[next].config.length < by {
+            return Err(next);
+        }
+
+        self.0[idx].config.length += by;
+        self.0[next].config.length -= by;
+        Ok(())
+    }
+
+    /// Sets each slot's requested length to what it was scheduled as in `results`, leaving fixed
+    /// and elastic slots as they are. Slots that couldn't be scheduled cleanly are skipped.
+    pub fn adopt_lengths(&mut self, results: &[SlotResult]) {
+        for (slot, result) in self.0.iter_mut().zip(results) {
+            if result.warning.is_ok() {
+                slot.config.length = result.length;
+            }
+        }
+    }
+
+    /// Gives the elastic slots of each block in `results` the same requested length and weight, so
+    /// they're scheduled equally long as far as their bounds allow. The length is their average, so
+    /// the time they ask for stays the same. Blocks without elastic slots are left alone.
+    pub fn even_out(&mut self, results: &[SlotResult]) {
+        let mut blocks: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (idx, result) in results.iter().enumerate().take(self.0.len()) {
+            if self.0[idx].is_elastic() {
+                blocks.entry(result.block).or_default().push(idx);
+            }
+        }
+
+        for elastic in blocks.values() {
+            let total: Duration = elastic.iter().map(|&idx| self.0[idx].config.length).sum();
+            let length = total / elastic.len() as i32;
+            for &idx in elastic {
+                self.0[idx].config.length = length;
+                self.0[idx].config.weight = default_weight();
+            }
+        }
+    }
+
+    /// Fixes the length of every slot in `block` to what it was scheduled as in `results`, or if
+    /// they're all fixed already, makes them elastic again.
+    pub fn toggle_frozen(&mut self, results: &[SlotResult], block: usize) {
+        let in_block: Vec<usize> = results
+            .iter()
+            .enumerate()
+            .filter(|(idx, result)| result.block == block && *idx < self.0.len())
+            .map(|(idx, _)| idx)
+            .collect();
+        let frozen = in_block.iter().all(|&idx| self.0[idx].config.fixed_length);
+
+        for idx in in_block {
+            let config = &mut self.0[idx].config;
+            if frozen {
+                config.fixed_length = false;
+            } else {
+                config.length = results[idx].length;
+                config.fixed_length = true;
+            }
+        }
+    }
+
+    /// A copy with no starts and nothing marked done, for planning another day the same way.
+    pub fn without_progress(&self) -> Self {
+        let mut out = self.clone();
+        for slot in &mut out.0 {
+            slot.config.start = None;
+            slot.done = false;
+        }
+        out
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Replaces the slot with two copies that each request half its length, rounded to the minute.
+    /// Only the first copy keeps a fixed start.
+    pub fn split(&mut self, idx: usize) {
+        if idx >= self.0.len() {
+            return;
+        }
+
+        let mut first = self.0[idx].clone();
+        let length = first.config.length;
+        let half = (length.num_seconds() as f32 / 120.0).round() as i64;
+        first.config.length = Duration::minutes(half);
+        // Whatever rounding gave the first half comes off the second, so the total stays the same.
+        let mut second = first.clone();
+        second.config.length = length - first.config.length;
+        second.config.start = None;
+
+        let prev = mem::replace(&mut self.0[idx], first);
+        self.0.insert(idx + 1, second);
+
+        if !self.is_valid() {
+            self.0.remove(idx + 1);
+            self.0[idx] = prev;
+        }
+    }
+
+    /// Folds the following slot into this one, adding up their lengths. Keeps the first slot's
+    /// name and start, and stays fixed length if either was.
+    pub fn merge_next(&mut self, idx: usize) {
+        if idx + 1 >= self.0.len() {
+            return;
+        }
+
+        let next = self.0.remove(idx + 1);
+        let prev = self.0[idx].config.clone();
+        let slot = &mut self.0[idx];
+        slot.config.length += next.config.length;
+        slot.config.fixed_length |= next.config.fixed_length;
+
+        if !self.is_valid() {
+            self.0[idx].config = prev;
+            self.0.insert(idx + 1, next);
+        }
+    }
+
+    /// Clears the starts that come before an earlier slot's start, returning which slots lost theirs.
+    pub fn make_valid(&mut self) -> Vec<usize> {
+        let dropped = Self::out_of_order_starts(&self.0);
+        for &idx in &dropped {
+            self.0[idx].config.start = None;
+        }
+        dropped
+    }
+
+    /// The slots whose start is before the start of a slot above them.
+    pub fn out_of_order_starts(slots: &[SlotDto]) -> Vec<usize> {
+        let mut last_start: Option<TimeSinceMidnight> = None;
+        let mut out = vec![];
+
+        for (idx, slot) in slots.iter().enumerate() {
+            let Some(start) = slot.config.start else {
+                continue;
+            };
+
+            if last_start.is_some_and(|last| start < last) {
+                out.push(idx);
+            } else {
+                last_start = Some(start);
+            }
+        }
+
+        out
+    }
+
+    fn is_valid(&self) -> bool {
+        Self::validate(&self.0).is_ok()
+    }
+
+    /// Checks that the starts are in order, failing with the index of the first one that isn't.
+    pub fn validate(slots: &[SlotDto]) -> Result<(), usize> {
+        let mut last_start: Option<TimeSinceMidnight> = None;
+
+        for (idx, slot) in slots.iter().enumerate() {
+            if let Some(t) = &slot.config.start {
+                if let Some(prev_t) = &last_start {
+                    if t < prev_t {
+                        return Err(idx);
+                    }
+                }
+                last_start = Some(*t);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the fixed starts fall within the window from `start_time` lasting `total_time`,
+    /// failing with the index of the first one that doesn't. A fixed start on the first slot moves
+    /// the window to it, as in [`calculate_slots`].
+    pub fn validate_within(
+        slots: &[SlotDto],
+        start_time: TimeSinceMidnight,
+        total_time: Duration,
+    ) -> Result<(), usize> {
+        let start_time = slots
+            .first()
+            .and_then(|slot| slot.config.start)
+            .unwrap_or(start_time);
+        let window = start_time..=start_time + total_time;
+
+        match slots.iter().position(|slot| {
+            slot.config
+                .start
+                .is_some_and(|start| !window.contains(&start))
+        }) {
+            Some(idx) => Err(idx),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(name: &str) -> SlotDto {
+        SlotDto {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn anchored(name: &str, start: TimeSinceMidnight) -> SlotDto {
+        let mut slot = slot(name);
+        slot.config.start = Some(start);
+        slot
+    }
+
+    #[test]
+    fn a_capped_slot_passes_its_surplus_on() {
+        let mut capped = slot("capped");
+        capped.config.max_length = Some(dur(60));
+        let results = calculate_slots(t(9, 0), dur(6 * 60), vec![capped, slot("a"), slot("b")]);
+
+        let lengths: Vec<Duration> = results.iter().map(|slot| slot.length).collect();
+        assert_eq!(lengths, vec![dur(60), dur(150), dur(150)]);
+    }
+
+    #[test]
+    fn time_freed_by_a_max_is_shared_out_again() {
+        let mut short = slot("short");
+        short.config.length = dur(10);
+        short.config.min_length = Some(dur(30));
+        let mut long = slot("long");
+        long.config.length = dur(90);
+        long.config.max_length = Some(dur(50));
+        let results = calculate_slots(t(9, 0), dur(100), vec![short, long]);
+
+        let lengths: Vec<Duration> = results.iter().map(|slot| slot.length).collect();
+        assert_eq!(lengths, vec![dur(50), dur(50)]);
+    }
+
+    #[test]
+    fn weights_split_a_block_by_ratio() {
+        let mut heavy = slot("heavy");
+        heavy.config.weight = 3.0;
+        let results = calculate_slots(t(9, 0), dur(4 * 60), vec![slot("light"), heavy]);
+
+        let lengths: Vec<Duration> = results.iter().map(|slot| slot.length).collect();
+        assert_eq!(lengths, vec![dur(60), dur(180)]);
+    }
+
+    #[test]
+    fn fixed_slots_that_dont_fit_are_squeezed_and_warned() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(120);
+        let results = calculate_slots(t(9, 0), dur(60), vec![fixed, slot("elastic")]);
+
+        assert_eq!(results[0].length, dur(60));
+        assert_eq!(
+            results[0].warning,
+            Err(ScheduleError::InsufficientFixedTime)
+        );
+        assert_eq!(results[1].length, Duration::zero());
+    }
+
+    #[test]
+    fn an_all_fixed_block_is_stretched_and_warned() {
+        let fixed = || {
+            let mut fixed = slot("fixed");
+            fixed.config.fixed_length = true;
+            fixed
+        };
+        let results = calculate_slots(t(9, 0), dur(3 * 60), vec![fixed(), fixed()]);
+
+        for slot in &results {
+            assert_eq!(slot.length, dur(90));
+            assert_eq!(slot.warning, Err(ScheduleError::NoElasticSlots));
+        }
+    }
+
+    #[test]
+    fn window_past_midnight_schedules_into_the_next_day() {
+        let results = calculate_slots(t(22, 0), dur(4 * 60), vec![slot("a"), slot("b")]);
+
+        assert_eq!(results[1].start, t(24, 0));
+        assert_eq!(results[1].start + results[1].length, t(26, 0));
+    }
+
+    #[test]
+    fn overcommitted_blocks_report_the_excess() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(120);
+        let mut min = slot("min");
+        min.config.min_length = Some(dur(30));
+        let slots = vec![slot("a"), anchored("b", t(12, 0)), fixed, min];
+
+        assert_eq!(
+            overcommitted_blocks(t(11, 0), dur(3 * 60), slots),
+            vec![(t(12, 0), dur(30))]
+        );
+    }
+
+    #[test]
+    fn filler_takes_the_leftover_after_fixed_slots() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        let mut filler = slot("filler");
+        filler.kind = SlotKind::Filler;
+        let results = calculate_slots(t(9, 0), dur(4 * 60), vec![fixed, slot("other"), filler]);
+
+        let lengths: Vec<Duration> = results.iter().map(|slot| slot.length).collect();
+        assert_eq!(lengths, vec![dur(60), Duration::zero(), dur(180)]);
+    }
+
+    #[test]
+    fn block_indices_increase_at_fixed_starts() {
+        let slots = vec![slot("a"), anchored("b", t(12, 0)), slot("c")];
+        let blocks: Vec<usize> = calculate_slots(t(7, 0), dur(16 * 60), slots)
+            .iter()
+            .map(|slot| slot.block)
+            .collect();
+
+        assert_eq!(blocks, vec![0, 1, 1]);
+    }
+
+    fn dtos(slots: Vec<SlotDto>) -> SlotDtos {
+        SlotDtos(slots)
+    }
+
+    #[test]
+    fn starts_before_the_first_fixed_start_are_invalid() {
+        assert!(SlotDtos::validate(&[anchored("a", t(9, 0)), anchored("b", t(12, 0))]).is_ok());
+        assert!(SlotDtos::validate(&[anchored("a", t(12, 0)), anchored("b", t(9, 0))]).is_err());
+    }
+
+    #[test]
+    fn toggling_done_goes_through_over_ride() {
+        let mut slots = dtos(vec![slot("a")]);
+
+        let mut slot = slots[0].clone();
+        slot.done = !slot.done;
+        slots.over_ride(0, slot);
+        assert!(slots[0].done);
+
+        let mut slot = slots[0].clone();
+        slot.done = !slot.done;
+        slots.over_ride(0, slot);
+        assert!(!slots[0].done);
+    }
+
+    #[test]
+    fn split_halves_the_length() {
+        let mut slots = dtos(vec![anchored("a", t(9, 0))]);
+        slots.split(0);
+
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0].config.length, dur(30));
+        assert_eq!(slots[1].config.length, dur(30));
+        assert_eq!(slots[0].config.start, Some(t(9, 0)));
+        assert_eq!(slots[1].config.start, None);
+    }
+
+    #[test]
+    fn split_keeps_an_odd_length() {
+        let mut a = slot("a");
+        a.config.length = dur(45);
+        let mut slots = dtos(vec![a]);
+        slots.split(0);
+
+        assert_eq!(slots[0].config.length, dur(23));
+        assert_eq!(slots[1].config.length, dur(22));
+    }
+
+    #[test]
+    fn merge_sums_lengths_and_keeps_starts_in_order() {
+        let mut b = anchored("b", t(10, 0));
+        b.config.length = dur(30);
+        let mut slots = dtos(vec![anchored("a", t(9, 0)), b, anchored("c", t(11, 0))]);
+        slots.merge_next(0);
+
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0].name, "a");
+        assert_eq!(slots[0].config.length, dur(90));
+        assert!(SlotDtos::validate(&slots).is_ok());
+    }
+
+    #[test]
+    fn clear_empties_the_day() {
+        let mut slots = dtos(vec![slot("a"), slot("b")]);
+        slots.clear();
+
+        assert!(slots.is_empty());
+    }
+
+    #[test]
+    fn rejected_edits_leave_slots_untouched() {
+        let original = dtos(vec![
+            anchored("a", t(9, 0)),
+            slot("b"),
+            anchored("c", t(12, 0)),
+        ]);
+        let mut slots = original.clone();
+
+        slots.set_start(1, t(13, 0));
+        slots.swap(0, 2);
+        slots.insert(0, anchored("d", t(10, 0)));
+        slots.over_ride(2, anchored("e", t(8, 0)));
+
+        assert!(slots == original);
+    }
+
+    #[test]
+    fn late_start_drops_overtaken_anchors() {
+        let mut slots = dtos(vec![
+            slot("a"),
+            anchored("b", t(10, 0)),
+            anchored("c", t(13, 0)),
+        ]);
+        slots.set_start_cascade(0, t(11, 0));
+
+        let starts: Vec<_> = slots.iter().map(|slot| slot.config.start).collect();
+        assert_eq!(starts, vec![Some(t(11, 0)), None, Some(t(13, 0))]);
+    }
+
+    #[test]
+    fn make_valid_drops_out_of_order_starts() {
+        let mut slots = dtos(vec![
+            anchored("a", t(10, 0)),
+            anchored("b", t(9, 0)),
+            anchored("c", t(11, 0)),
+        ]);
+
+        assert_eq!(slots.make_valid(), vec![1]);
+        assert_eq!(slots[1].config.start, None);
+        assert!(SlotDtos::validate(&slots).is_ok());
+    }
+
+    #[test]
+    fn without_progress_keeps_everything_but_starts_and_done() {
+        let mut done = anchored("a", t(9, 0));
+        done.done = true;
+        let slots = dtos(vec![done, slot("b")]);
+
+        let mut expected = slots.clone();
+        for slot in &mut expected.0 {
+            slot.config.start = None;
+            slot.done = false;
+        }
+        assert!(slots.without_progress() == expected);
+    }
+
+    /// Each block as its start, end and the names of its slots.
+    fn blocks(
+        start: TimeSinceMidnight,
+        total: Duration,
+        slots: Vec<SlotDto>,
+    ) -> Vec<(TimeSinceMidnight, TimeSinceMidnight, Vec<String>)> {
+        get_slotblocks(start, total, NonEmpty::from_vec(slots).unwrap())
+            .into_iter()
+            .map(|block| {
+                let names = block.slots.iter().map(|slot| slot.name.clone()).collect();
+                (block.start, block.end_time, names)
+            })
+            .collect()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn no_fixed_starts_is_one_block() {
+        let blocks = blocks(t(7, 0), dur(16 * 60), vec![slot("a"), slot("b"), slot("c")]);
+
+        assert_eq!(blocks, vec![(t(7, 0), t(23, 0), names(&["a", "b", "c"]))]);
+    }
+
+    #[test]
+    fn fixed_start_on_first_slot_moves_the_window() {
+        let slots = vec![anchored("a", t(9, 0)), slot("b")];
+
+        // get_slotblocks itself doesn't split at the first slot's start...
+        let at_default = blocks(t(7, 0), dur(16 * 60), slots.clone());
+        assert_eq!(at_default, vec![(t(7, 0), t(23, 0), names(&["a", "b"]))]);
+
+        // ...calculate_slots moves the whole window to it instead, keeping its length.
+        let results = calculate_slots(t(7, 0), dur(16 * 60), slots);
+        assert_eq!(results[0].start, t(9, 0));
+        let last = results.last().unwrap();
+        assert_eq!(last.start + last.length, t(25, 0));
+    }
+
+    fn soft(name: &str, start: TimeSinceMidnight) -> SlotDto {
+        let mut slot = slot(name);
+        slot.config.soft_start = Some(start);
+        slot
+    }
+
+    #[test]
+    fn soft_start_anchors_when_earlier_slots_fit() {
+        let slots = vec![slot("a"), soft("b", t(12, 0)), slot("c")];
+
+        assert_eq!(
+            blocks(t(7, 0), dur(16 * 60), slots),
+            vec![
+                (t(7, 0), t(12, 0), names(&["a"])),
+                (t(12, 0), t(23, 0), names(&["b", "c"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn soft_start_slides_when_earlier_slots_overrun_it() {
+        // a asks for 1h from 7:00, so b can't start at 7:30, and c comes after 2h of a and b.
+        let slots = vec![slot("a"), soft("b", t(7, 30)), soft("c", t(8, 30))];
+
+        assert_eq!(
+            blocks(t(7, 0), dur(16 * 60), slots),
+            vec![(t(7, 0), t(23, 0), names(&["a", "b", "c"]))]
+        );
+    }
+
+    #[test]
+    fn soft_start_never_passes_the_next_fixed_start() {
+        let slots = vec![soft("a", t(12, 0)), anchored("b", t(10, 0)), slot("c")];
+
+        assert_eq!(
+            blocks(t(7, 0), dur(16 * 60), slots),
+            vec![
+                (t(7, 0), t(10, 0), names(&["a"])),
+                (t(10, 0), t(23, 0), names(&["b", "c"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_start_in_the_middle_splits_in_two() {
+        let slots = vec![slot("a"), anchored("b", t(12, 0)), slot("c")];
+
+        assert_eq!(
+            blocks(t(7, 0), dur(16 * 60), slots),
+            vec![
+                (t(7, 0), t(12, 0), names(&["a"])),
+                (t(12, 0), t(23, 0), names(&["b", "c"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn consecutive_fixed_starts_each_begin_a_block() {
+        let slots = vec![
+            slot("a"),
+            anchored("b", t(12, 0)),
+            anchored("c", t(14, 0)),
+            slot("d"),
+        ];
+
+        assert_eq!(
+            blocks(t(7, 0), dur(16 * 60), slots),
+            vec![
+                (t(7, 0), t(12, 0), names(&["a"])),
+                (t(12, 0), t(14, 0), names(&["b"])),
+                (t(14, 0), t(23, 0), names(&["c", "d"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_survive_serialization() {
+        let mut with_note = slot("standup");
+        with_note.note = Some("demo the new export".to_string());
+        let without_note = slot("lunch");
+
+        for slot in [with_note, without_note] {
+            let json = serde_json::to_string(&slot).unwrap();
+            assert_eq!(serde_json::from_str::<SlotDto>(&json).unwrap(), slot);
+        }
+        assert!(!serde_json::to_string(&slot("lunch"))
+            .unwrap()
+            .contains("note"));
+    }
+
+    #[test]
+    fn freezing_a_block_keeps_its_layout() {
+        let mut fixed = slot("b");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(45);
+        let mut slots = dtos(vec![slot("a"), fixed, slot("c"), anchored("d", t(12, 0))]);
+        let before = calculate_slots(t(7, 0), dur(16 * 60), slots.to_vec());
+
+        slots.toggle_frozen(&before, 0);
+        assert!(slots[..3].iter().all(|slot| slot.config.fixed_length));
+        assert!(!slots[3].config.fixed_length);
+
+        let after = calculate_slots(t(7, 0), dur(16 * 60), slots.to_vec());
+        for (before, after) in before.iter().zip(&after) {
+            assert!((before.length - after.length).num_seconds().abs() <= 1);
+            assert_eq!(after.warning, Ok(()));
+        }
+
+        slots.toggle_frozen(&after, 0);
+        assert!(slots.iter().all(|slot| !slot.config.fixed_length));
+    }
+
+    #[test]
+    fn adopted_lengths_schedule_the_same() {
+        let mut short = slot("a");
+        short.config.length = dur(20);
+        let mut slots = dtos(vec![short, slot("b"), anchored("c", t(12, 0)), slot("d")]);
+        let before = calculate_slots(t(7, 0), dur(16 * 60), slots.to_vec());
+
+        slots.adopt_lengths(&before);
+        assert!(slots.iter().all(|slot| !slot.config.fixed_length));
+        assert_eq!(slots[0].config.length, before[0].length);
+
+        let after = calculate_slots(t(7, 0), dur(16 * 60), slots.to_vec());
+        for (before, after) in before.iter().zip(&after) {
+            assert_eq!(before.start, after.start);
+            assert!((before.length - after.length).num_seconds().abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn adopting_an_overcommitted_block_keeps_its_elastic_lengths() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(120);
+        let mut slots = dtos(vec![fixed, slot("elastic"), anchored("next", t(10, 0))]);
+        let results = calculate_slots(t(9, 0), dur(16 * 60), slots.to_vec());
+        assert_eq!(results[1].length, Duration::zero());
+
+        slots.adopt_lengths(&results);
+        assert_eq!(slots[1].config.length, dur(60));
+    }
+
+    #[test]
+    fn starts_outside_the_window_are_invalid() {
+        let window = |slots: Vec<SlotDto>| SlotDtos::validate_within(&slots, t(7, 0), dur(16 * 60));
+
+        assert_eq!(window(vec![slot("a"), anchored("b", t(23, 0))]), Ok(()));
+        assert_eq!(window(vec![slot("a"), anchored("b", t(23, 30))]), Err(1));
+        assert_eq!(window(vec![slot("a"), anchored("b", t(3, 0))]), Err(1));
+        // The first slot's start moves the window.
+        assert_eq!(
+            window(vec![anchored("a", t(9, 0)), anchored("b", t(24, 30))]),
+            Ok(())
+        );
+
+        // Even unchecked, a start past the window doesn't split the day.
+        let results = calculate_slots(
+            t(7, 0),
+            dur(16 * 60),
+            vec![slot("a"), anchored("b", t(23, 30))],
+        );
+        assert_eq!(results[1].block, 0);
+    }
+
+    #[test]
+    fn shifting_moves_every_fixed_start() {
+        let mut slots = dtos(vec![
+            slot("a"),
+            anchored("b", t(9, 0)),
+            anchored("c", t(12, 0)),
+        ]);
+
+        assert_eq!(slots.shift_starts(dur(30), t(7, 0), dur(16 * 60)), Ok(()));
+        let starts: Vec<_> = slots.iter().map(|slot| slot.config.start).collect();
+        assert_eq!(starts, vec![None, Some(t(9, 30)), Some(t(12, 30))]);
+
+        // Out of the window, and nothing moves.
+        assert_eq!(
+            slots.shift_starts(dur(-3 * 60), t(7, 0), dur(16 * 60)),
+            Err(1)
+        );
+        assert_eq!(slots[1].config.start, Some(t(9, 30)));
+
+        // Right up to the end of the window is still in it.
+        assert_eq!(
+            slots.shift_starts(dur(10 * 60 + 30), t(7, 0), dur(16 * 60)),
+            Ok(())
+        );
+        assert_eq!(slots[2].config.start, Some(t(23, 0)));
+    }
+
+    #[test]
+    fn evened_out_slots_share_their_block_equally() {
+        let mut heavy = slot("heavy");
+        heavy.config.weight = 3.0;
+        let mut short = slot("short");
+        short.config.length = dur(15);
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(30);
+        let mut slots = dtos(vec![heavy, fixed, short, slot("plain")]);
+        let window = |slots: &SlotDtos| calculate_slots(t(9, 0), dur(6 * 60), slots.to_vec());
+
+        slots.even_out(&window(&slots));
+        let lengths: Vec<Duration> = window(&slots).iter().map(|slot| slot.length).collect();
+        assert_eq!(lengths, vec![dur(110), dur(30), dur(110), dur(110)]);
+    }
+
+    #[test]
+    fn allocations_are_given_per_block() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        let slots = vec![fixed, slot("a"), slot("b"), anchored("c", t(17, 0))];
+        let allocations = block_allocations(t(9, 0), dur(10 * 60), slots);
+
+        let debug: Vec<String> = allocations
+            .iter()
+            .map(|alloc| format!("{alloc:?}"))
+            .collect();
+        assert_eq!(
+            debug,
+            vec![
+                "tot alloc 8h, tot req fixed: 1h, tot_req_elastic: 2h, elastic alloc time: 7h ",
+                "tot alloc 2h, tot req fixed: 0s, tot_req_elastic: 1h, elastic alloc time: 2h ",
+            ]
+        );
+    }
+
+    #[test]
+    fn unfixing_as_scheduled_keeps_the_length() {
+        let mut fixed = slot("fixed");
+        fixed.config.fixed_length = true;
+        fixed.config.length = dur(90);
+        let mut slots = dtos(vec![fixed, slot("a"), slot("b")]);
+        let before = calculate_slots(t(9, 0), dur(210), slots.to_vec());
+
+        slots.toggle_fixed_as_scheduled(0, &before);
+        assert!(!slots[0].config.fixed_length);
+        let after = calculate_slots(t(9, 0), dur(210), slots.to_vec());
+        let lengths = |results: &[SlotResult]| results.iter().map(|r| r.length).collect::<Vec<_>>();
+        assert_eq!(lengths(&after), lengths(&before));
+
+        slots.toggle_fixed_as_scheduled(0, &before);
+        assert!(slots[0].config.fixed_length);
+    }
+
+    #[test]
+    fn borrowing_takes_from_the_next_slot() {
+        let mut slots = dtos(vec![slot("a"), slot("b")]);
+
+        assert_eq!(slots.borrow_from_next(0, dur(45)), Ok(()));
+        assert_eq!(slots[0].config.length, dur(105));
+        assert_eq!(slots[1].config.length, dur(15));
+
+        assert_eq!(slots.borrow_from_next(0, dur(16)), Err(1));
+        assert_eq!(slots.borrow_from_next(1, dur(1)), Err(2));
+        assert_eq!(slots[0].config.length, dur(105));
+        assert_eq!(slots[1].config.length, dur(15));
+
+        assert_eq!(slots.borrow_from_next(0, dur(15)), Ok(()));
+        assert_eq!(slots[1].config.length, Duration::zero());
+    }
+}
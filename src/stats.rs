@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use vedvaring::{FsTrait, Saved};
+
+use crate::{format_dur, Act, ActId, Day};
+
+/// The saved days between `from` and `to`, inclusive. Days that were never saved are skipped.
+pub fn load_days(from: NaiveDate, to: NaiveDate) -> Vec<Saved<Day>> {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .filter_map(Saved::load)
+        .collect()
+}
+
+/// Every saved day, oldest first.
+pub fn all_days() -> Vec<Saved<Day>> {
+    let Ok(entries) = std::fs::read_dir(Day::items_path()) else {
+        return vec![];
+    };
+
+    // Backups sit next to the day files, so only names that are dates count.
+    let mut dates: Vec<NaiveDate> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    dates.sort();
+
+    dates.into_iter().filter_map(Saved::load).collect()
+}
+
+/// Prints the total planned time per activity, longest first.
+///
+/// Slots linked to a saved activity are grouped under it, the rest by their name.
+pub fn print_activity_stats(from: NaiveDate, to: NaiveDate) {
+    let mut totals: HashMap<Result<ActId, String>, (String, Duration)> = HashMap::new();
+
+    for day in load_days(from, to) {
+        for slot in day.read().slots().iter() {
+            let name = &slot.configured.name;
+            let key = slot.configured.act.ok_or_else(|| name.clone());
+            let entry = totals
+                .entry(key)
+                .or_insert_with(|| (name.clone(), Duration::zero()));
+            entry.1 += slot.length;
+        }
+    }
+
+    let mut rows: Vec<(String, Duration)> = totals
+        .into_iter()
+        .map(|(key, (name, total))| {
+            let name = key
+                .ok()
+                .and_then(Act::load)
+                .map(|act| act.name)
+                .unwrap_or(name);
+            (name, total)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    for (name, total) in rows {
+        println!("{}  {name}", format_dur(total));
+    }
+}
+
+/// How wide the longest bar of a chart is.
+const BAR_WIDTH: i64 = 40;
+
+/// Prints the total planned time per weekday over every saved day, as a bar chart.
+pub fn print_weekday_stats() {
+    let days = all_days();
+    let totals = planned_by_weekday(days.iter().map(|day| {
+        let day = day.read();
+        (day.day, day.planned())
+    }));
+
+    let longest = totals.values().max().copied().unwrap_or_default();
+    let mut weekday = Weekday::Mon;
+    for _ in 0..7 {
+        let total = totals.get(&weekday).copied().unwrap_or_default();
+        let bar = if longest.is_zero() {
+            0
+        } else {
+            total.num_seconds() * BAR_WIDTH / longest.num_seconds()
+        };
+        println!(
+            "{weekday}  {}  {}",
+            format_dur(total),
+            "#".repeat(bar as usize)
+        );
+        weekday = weekday.succ();
+    }
+}
+
+/// The planned time of each day summed up per weekday. Days with nothing planned add nothing.
+fn planned_by_weekday(
+    days: impl IntoIterator<Item = (NaiveDate, Duration)>,
+) -> HashMap<Weekday, Duration> {
+    let mut totals = HashMap::new();
+    for (date, planned) in days {
+        if planned.is_zero() {
+            continue;
+        }
+        *totals.entry(date.weekday()).or_insert_with(Duration::zero) += planned;
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SlotDefaults;
+    use dagplan::slot::dur;
+    use vedvaring::DefaultWithId;
+
+    #[test]
+    fn planned_time_adds_up_per_weekday() {
+        let day = |date: &str, lengths: &[i64]| {
+            let mut day = Day::default_with_id(date.parse().unwrap());
+            for (i, length) in lengths.iter().enumerate() {
+                day.insert(i, &SlotDefaults::default());
+                let mut slot = day.slots_config[i].clone();
+                slot.config.length = dur(*length);
+                day.slots_config.over_ride(i, slot);
+            }
+            day
+        };
+        // Two Fridays and a Saturday with nothing planned.
+        let days = [
+            day("2025-03-21", &[60, 30]),
+            day("2025-03-28", &[120]),
+            day("2025-03-29", &[]),
+        ];
+
+        let totals = planned_by_weekday(days.iter().map(|day| (day.day, day.planned())));
+
+        assert_eq!(totals, HashMap::from([(Weekday::Fri, dur(210))]));
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use dagplan::slot::{SlotDto, SlotDtos};
+use serde::{Deserialize, Serialize};
+
+/// `~/.config/dagplan/templates.json`, a list of named slot snippets like
+/// `[{"name": "pomodoro", "slots": [...]}]`, with slots written as they are in a saved day.
+pub fn templates_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("dagplan").join("templates.json"))
+}
+
+/// A run of slots that's often planned together, like 25 minutes of focus and a 5 minute break.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Template {
+    pub name: String,
+    pub slots: Vec<SlotDto>,
+}
+
+/// Reads the user's templates. A missing file means there are none.
+pub fn load_templates(path: &Path) -> Result<Vec<Template>, String> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let s = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_str(&s).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Inserts the template's slots at `index`, in order. Their starts are dropped so they can't
+/// conflict with the starts already in the day. Returns how many slots went in.
+pub fn insert_template(slots: &mut SlotDtos, index: usize, template: &Template) -> usize {
+    let before = slots.len();
+    let mut index = index.min(before);
+    for slot in &template.slots {
+        let mut slot = slot.clone();
+        slot.config.start = None;
+        slot.config.soft_start = None;
+        slot.done = false;
+
+        let len = slots.len();
+        slots.insert(index, slot);
+        if slots.len() > len {
+            index += 1;
+        }
+    }
+    slots.len() - before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dagplan::slot::{dur, t};
+
+    fn slot(name: &str, mins: i64) -> SlotDto {
+        let mut slot = SlotDto {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        slot.config.length = dur(mins);
+        slot
+    }
+
+    #[test]
+    fn inserting_a_template_adds_its_slots_without_starts() {
+        let mut focus = slot("focus", 25);
+        focus.config.start = Some(t(7, 0));
+        let template = Template {
+            name: "pomodoro".to_string(),
+            slots: vec![focus, slot("break", 5)],
+        };
+
+        let mut day = SlotDtos::default();
+        day.insert(0, slot("email", 30));
+        let mut lunch = slot("lunch", 60);
+        lunch.config.start = Some(t(12, 0));
+        day.insert(1, lunch);
+
+        assert_eq!(insert_template(&mut day, 1, &template), 2);
+        let names: Vec<&str> = day.iter().map(|slot| slot.name.as_str()).collect();
+        assert_eq!(names, vec!["email", "focus", "break", "lunch"]);
+        assert_eq!(day[1].config.start, None);
+    }
+}
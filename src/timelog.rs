@@ -0,0 +1,68 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use dagplan::slot::{SlotKind, SlotResult};
+use serde::Serialize;
+
+/// A slot as it actually ran, one JSON line in the time log.
+#[derive(Serialize)]
+struct Entry<'a> {
+    name: &'a str,
+    #[serde(rename = "break")]
+    is_break: bool,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    minutes: i64,
+}
+
+/// The log line for `slot` having been active from `start` until `end`, without the newline.
+pub fn log_line(slot: &SlotResult, start: NaiveDateTime, end: NaiveDateTime) -> String {
+    let entry = Entry {
+        name: &slot.configured.name,
+        is_break: slot.configured.kind == SlotKind::Break,
+        start,
+        end,
+        minutes: (end - start).num_minutes(),
+    };
+    serde_json::to_string(&entry).unwrap()
+}
+
+/// Appends `line` to the log of `day` in `dir`, `<day>.jsonl`.
+pub fn append(dir: &Path, day: NaiveDate, line: &str) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let path = dir.join(format!("{day}.jsonl"));
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    writeln!(file, "{line}").map_err(|e| format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dagplan::slot::{dur, t, SlotDto};
+
+    #[test]
+    fn log_line_records_when_the_slot_really_ran() {
+        let slot = SlotResult {
+            start: t(9, 0),
+            length: dur(60),
+            warning: Ok(()),
+            configured: SlotDto {
+                name: "code review".to_string(),
+                ..Default::default()
+            },
+            block: 0,
+        };
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+
+        assert_eq!(
+            log_line(&slot, at("2025-03-28 09:05"), at("2025-03-28 10:20")),
+            r#"{"name":"code review","break":false,"start":"2025-03-28T09:05:00","end":"2025-03-28T10:20:00","minutes":75}"#
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use dagplan::slot::{SlotDto, SlotDtos};
+
+use crate::{diagnostics, Day, PlanWindow};
+
+/// Reads a saved day or slots exported as JSON and lists what's wrong with them, planned within
+/// `window`. Fails if the file can't be read as either.
+pub fn check_file(path: &Path, window: PlanWindow) -> Result<Vec<String>, String> {
+    let s = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let slots = if s.trim_start().starts_with('[') {
+        serde_json::from_str::<SlotDtos>(&s).map_err(|e| format!("{}: {e}", path.display()))?
+    } else {
+        Day::from_json(&s)
+            .map_err(|e| format!("{}: {e}", path.display()))?
+            .slots_config
+    };
+
+    Ok(problems(&slots, window))
+}
+
+/// Starts out of order or outside the window, and blocks that ask for more time than they have.
+/// Blocks are only checked once the starts are fine, as they're made from the starts.
+fn problems(slots: &[SlotDto], window: PlanWindow) -> Vec<String> {
+    if let Err(idx) = SlotDtos::validate(slots) {
+        return vec![format!(
+            "slot {} starts before the start of a slot above it",
+            idx + 1
+        )];
+    }
+    if let Err(idx) = SlotDtos::validate_within(slots, window.start, window.total) {
+        return vec![format!("slot {} starts outside the day", idx + 1)];
+    }
+
+    diagnostics(slots, window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dagplan::slot::t;
+
+    fn check(json: &str) -> Result<Vec<String>, String> {
+        let dir = std::env::temp_dir().join(format!("dagplan-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2025-03-28");
+        std::fs::write(&path, json).unwrap();
+
+        let result = check_file(&path, PlanWindow::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+        result
+    }
+
+    fn slots(starts: &[u32]) -> SlotDtos {
+        let mut slots = SlotDtos::default();
+        for hour in starts {
+            let mut slot = SlotDto::default();
+            slot.config.start = Some(t(*hour, 0));
+            slots.insert(slots.len(), slot);
+        }
+        slots
+    }
+
+    #[test]
+    fn valid_files_have_no_problems() {
+        let day = format!(
+            r#"{{"day": "2025-03-28", "slots_config": {}}}"#,
+            serde_json::to_string(&slots(&[9, 12])).unwrap()
+        );
+        assert_eq!(check(&day), Ok(vec![]));
+
+        let exported = serde_json::to_string(&slots(&[9, 12])).unwrap();
+        assert_eq!(check(&exported), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_files_are_reported() {
+        let mut out_of_order = slots(&[9, 12]).to_vec();
+        out_of_order.swap(0, 1);
+        let json = serde_json::to_string(&out_of_order).unwrap();
+        assert_eq!(
+            check(&json),
+            Ok(vec![
+                "slot 2 starts before the start of a slot above it".to_string()
+            ])
+        );
+
+        assert!(check(r#"{"day": "2025-03-28", "slots_con"#).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh data and config directory, so the user's own days and settings stay out of it.
+fn scratch_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dagplan-export-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn export_json(dir: &Path, date: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dagplan"))
+        .args(["export", "--format", "json", "--date", date])
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn json_export_prints_the_saved_slots() {
+    let dir = scratch_dir();
+    let days = dir.join("dagplan").join("dagplan::Day");
+    fs::create_dir_all(&days).unwrap();
+    let day = r#"{
+        "day": "2025-03-28",
+        "slots_config": [{
+            "name": "review",
+            "act": null,
+            "config": {"start": null, "length": [1800, 0], "fixed_length": false},
+            "kind": "Activity",
+            "done": false
+        }]
+    }"#;
+    fs::write(days.join("2025-03-28"), day).unwrap();
+
+    let output = export_json(&dir, "2025-03-28");
+    let missing = export_json(&dir, "2025-03-29");
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported[0]["name"], "review");
+    assert!(!missing.status.success());
+}